@@ -0,0 +1,127 @@
+//! DOM (roxmltree) ve akış (streaming) tabanlı indeks parser'larının karşılaştırması.
+//!
+//! Gerçek bir indeksle çalıştırmak için:
+//!     PISI_INDEX=/var/lib/pisi/index/stable2/pisi-index.xml cargo bench --bench index_parse
+//! Değişken verilmezse sentetik bir indeks üretilir.
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use pm_core::backend::xml_parser::XmlParser;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::fs;
+use std::io::BufReader;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Anlık ve tepe (peak) heap kullanımını izleyen ayırıcı
+struct PeakAlloc;
+
+static CURRENT: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAlloc {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = System.alloc(layout);
+        if !ptr.is_null() {
+            let now = CURRENT.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+            PEAK.fetch_max(now, Ordering::Relaxed);
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout);
+        CURRENT.fetch_sub(layout.size(), Ordering::Relaxed);
+    }
+}
+
+#[global_allocator]
+static GLOBAL: PeakAlloc = PeakAlloc;
+
+fn synthetic_index(package_count: usize) -> String {
+    let mut xml = String::from("<?xml version=\"1.0\" ?>\n<PISI>\n<Distribution><SourceName>PisiLinux</SourceName><Version>2.0</Version></Distribution>\n");
+    for i in 0..package_count {
+        xml.push_str(&format!(
+            r#"<Package>
+    <Name>package-{i}</Name>
+    <Summary xml:lang="en">Summary of package {i} &amp; friends</Summary>
+    <Summary xml:lang="tr">Paket {i} özeti</Summary>
+    <Description xml:lang="en">A fairly long description for package {i}, repeated to look like a real index entry. A fairly long description for package {i}.</Description>
+    <IsA>app:gui</IsA>
+    <PartOf>desktop.misc</PartOf>
+    <License>GPLv2</License>
+    <RuntimeDependencies>
+        <Dependency versionFrom="2.30">glibc</Dependency>
+        <Dependency release="3">package-{dep}</Dependency>
+    </RuntimeDependencies>
+    <History>
+        <Update release="3"><Date>2024-01-03</Date><Version>1.{i}.2</Version><Comment>Version bump</Comment><Name>Packager</Name><Email>packager@pisilinux.org</Email></Update>
+        <Update release="2"><Date>2023-06-01</Date><Version>1.{i}.1</Version><Comment>Rebuild</Comment><Name>Packager</Name><Email>packager@pisilinux.org</Email></Update>
+    </History>
+    <Source><Name>package-{i}</Name><Homepage>https://example.org/{i}</Homepage><Packager><Name>Packager</Name><Email>packager@pisilinux.org</Email></Packager></Source>
+    <Distribution>PisiLinux</Distribution>
+    <DistributionRelease>2.0</DistributionRelease>
+    <Architecture>x86_64</Architecture>
+    <InstalledSize>{installed}</InstalledSize>
+    <PackageSize>{size}</PackageSize>
+    <PackageHash>0123456789abcdef0123456789abcdef01234567</PackageHash>
+    <PackageURI>p/package-{i}/package-{i}-1.{i}.2-3-p2-x86_64.pisi</PackageURI>
+    <PackageFormat>1.2</PackageFormat>
+</Package>
+"#,
+            i = i,
+            dep = (i + 1) % package_count,
+            installed = 1024 * (i + 1),
+            size = 512 * (i + 1),
+        ));
+    }
+    xml.push_str("</PISI>\n");
+    xml
+}
+
+fn index_path() -> PathBuf {
+    if let Ok(path) = std::env::var("PISI_INDEX") {
+        return PathBuf::from(path);
+    }
+
+    let path = std::env::temp_dir().join("pm-bench-pisi-index.xml");
+    fs::write(&path, synthetic_index(20_000)).expect("failed to write synthetic index");
+    path
+}
+
+fn parse_dom(path: &PathBuf) -> usize {
+    let content = fs::read_to_string(path).unwrap();
+    XmlParser::parse_pisi_index(&content).unwrap().len()
+}
+
+fn parse_stream(path: &PathBuf) -> usize {
+    let file = fs::File::open(path).unwrap();
+    XmlParser::parse_pisi_index_reader(BufReader::new(file)).unwrap().len()
+}
+
+/// Bir çalıştırmanın, başlangıca göre ek tepe heap kullanımını ölçer
+fn measure_peak(f: impl FnOnce() -> usize) -> (usize, usize) {
+    let base = CURRENT.load(Ordering::Relaxed);
+    PEAK.store(base, Ordering::Relaxed);
+    let count = f();
+    (count, PEAK.load(Ordering::Relaxed) - base)
+}
+
+fn bench_index_parse(c: &mut Criterion) {
+    let path = index_path();
+
+    let (dom_count, dom_peak) = measure_peak(|| parse_dom(&path));
+    let (stream_count, stream_peak) = measure_peak(|| parse_stream(&path));
+    assert_eq!(dom_count, stream_count, "DOM and streaming parsers disagree");
+
+    println!("peak heap (DOM):       {:>8.1} MiB", dom_peak as f64 / 1048576.0);
+    println!("peak heap (streaming): {:>8.1} MiB", stream_peak as f64 / 1048576.0);
+
+    let mut group = c.benchmark_group("pisi_index");
+    group.sample_size(10);
+    group.bench_function("dom", |b| b.iter(|| parse_dom(&path)));
+    group.bench_function("streaming", |b| b.iter(|| parse_stream(&path)));
+    group.finish();
+}
+
+criterion_group!(benches, bench_index_parse);
+criterion_main!(benches);
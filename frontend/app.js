@@ -37,6 +37,8 @@ function setupMockInvoke() {
                 return { total_count: 5, installed_count: 0, available_count: 5, updates_count: 1 };
             case 'get_packages':
                 return [{ name: 'firefox', summary: 'Mozilla Firefox', part_of: 'desktop.web', package_size: 97000000 }];
+            case 'get_upgradable_packages':
+                return [];
             case 'get_components':
                 return [{ name: 'All', package_count: 1 }, { name: 'desktop.web', package_count: 1 }];
             default:
@@ -49,12 +51,14 @@ function setupMockInvoke() {
 let packages = [];
 let installedPackageNames = [];
 let upgradablePackageNames = [];
+let heldPackageNames = [];
 let filteredPackages = [];
 let selectedPackage = null;
 let currentCategory = 'all';
 let currentComponent = 'all';
 let currentFilter = 'all';
 let isDarkMode = localStorage.getItem('darkMode') === 'true';
+let currentOperationId = null;
 
 // Elements
 let elements = {};
@@ -97,16 +101,11 @@ document.addEventListener('DOMContentLoaded', async () => {
     // Event listener'ları bağla
     initializeEventListeners();
 
-    // Veriyi yüklemeden önce depoları güncelle
-    console.log('Updating repositories...');
-    try {
-        await invoke('update_repo');
-        console.log('Repositories updated successfully.');
-    } catch (e) {
-        console.error('Failed to update repositories:', e);
-    }
+    // pisi işlemlerinin ilerlemesini dinle
+    window.__TAURI__?.event?.listen('operation-progress', (event) => renderProgress(event.payload));
 
-    // Veriyi yükle
+    // Veriyi yükle. Depo güncellemesi yetki istediği için açılışta yapılmaz;
+    // ayarlardaki depo listesinden kullanıcı başlatır.
     await refreshData();
 });
 
@@ -125,11 +124,15 @@ function updateElementReferences() {
         installedCount: document.getElementById('installed-count'),
         availableCount: document.getElementById('available-count'),
         updatesCount: document.getElementById('updates-count'),
+        heldCount: document.getElementById('held-count'),
         sidebarTotal: document.getElementById('sidebar-total-count'),
         sidebarInstalled: document.getElementById('sidebar-installed-count'),
         sidebarUpdates: document.getElementById('sidebar-updates-count'),
         sidebarCompTotal: document.getElementById('sidebar-comp-total-count'),
-        langSelect: document.getElementById('lang-select')
+        langSelect: document.getElementById('lang-select'),
+        operationProgress: document.getElementById('operation-progress'),
+        progressFill: document.getElementById('progress-fill'),
+        progressText: document.getElementById('progress-text')
     };
 
     if (elements.langSelect) {
@@ -142,25 +145,29 @@ async function refreshData() {
         showLoading(true);
 
         // Verileri paralel olarak çek
-        const [pkgs, installed, upgradable, components] = await Promise.all([
+        const [pkgs, installed, upgradable, components, held] = await Promise.all([
             invoke('get_packages'),
             invoke('get_installed_packages'),
             invoke('get_upgradable_packages'),
-            invoke('get_components')
+            invoke('get_components'),
+            invoke('get_held_packages')
         ]);
 
         packages = pkgs;
         installedPackageNames = installed;
-        upgradablePackageNames = upgradable;
+        // Sabitlenmiş paketler güncellemelerden ayrı gösterilir
+        upgradablePackageNames = upgradable.filter(p => !p.held).map(p => p.name);
+        heldPackageNames = held;
 
-        console.log(`Data loaded: ${packages.length} total, ${installed.length} installed, ${upgradable.length} updates`);
+        console.log(`Data loaded: ${packages.length} total, ${installed.length} installed, ${upgradablePackageNames.length} updates`);
 
         // İstatistikleri yerel veriden güncelle
         const stats = {
             total_count: packages.length,
             installed_count: installed.length,
             available_count: Math.max(0, packages.length - installed.length),
-            updates_count: upgradable.length
+            updates_count: upgradablePackageNames.length,
+            held_count: heldPackageNames.length
         };
         updateUIStats(stats);
 
@@ -177,6 +184,7 @@ function updateUIStats(stats) {
     if (elements.installedCount) elements.installedCount.textContent = stats.installed_count;
     if (elements.availableCount) elements.availableCount.textContent = stats.available_count;
     if (elements.updatesCount) elements.updatesCount.textContent = stats.updates_count;
+    if (elements.heldCount) elements.heldCount.textContent = stats.held_count ?? 0;
 
     if (elements.sidebarTotal) elements.sidebarTotal.textContent = stats.total_count;
     if (elements.sidebarInstalled) elements.sidebarInstalled.textContent = stats.installed_count;
@@ -201,6 +209,44 @@ function initializeEventListeners() {
 
     document.getElementById('settings-btn')?.addEventListener('click', () => {
         if (elements.settingsModal) elements.settingsModal.style.display = 'block';
+        loadRepositories();
+    });
+
+    document.getElementById('mirror-test-btn')?.addEventListener('click', async () => {
+        try {
+            showLoading(true);
+            const report = await invoke('test_mirrors', { force: true });
+            alert(report.results.map(r => r.error
+                ? `✗ [${r.mirror.repository}] ${r.mirror.url}\n    ${formatError(r.error)}`
+                : `✓ [${r.mirror.repository}] ${r.mirror.url}${r.mirror.location ? ` (${r.mirror.location})` : ''}\n    ${r.latency_ms} ms · ${formatSize(r.throughput_bps)}/s`
+            ).join('\n'));
+        } catch (e) { showError(e); }
+        finally { showLoading(false); }
+    });
+
+    document.getElementById('repo-add-btn')?.addEventListener('click', async () => {
+        const nameInput = document.getElementById('repo-name');
+        const urlInput = document.getElementById('repo-url');
+        const positionInput = document.getElementById('repo-position');
+        const name = nameInput.value.trim();
+        const url = urlInput.value.trim();
+        if (!name || !url) return;
+        // Listede sıra 1'den başlar, pisi'de 0'dan
+        const position = positionInput.value ? Math.max(0, Number(positionInput.value) - 1) : null;
+        try {
+            // Adres ve indeks eklemeden önce denetlenir; uyumsuz depolar eklenmez
+            const probe = await invoke('probe_repository', { url });
+            if (probe.mismatches.length) {
+                const lines = probe.mismatches.map(m => `  • ${m.field}: ${m.found} (${i18n.t('expected')}: ${m.expected})`);
+                alert(`${i18n.t('repo_mismatch')}\n\n${lines.join('\n')}`);
+                return;
+            }
+            await invoke('add_repository', { name, url, position });
+            nameInput.value = '';
+            urlInput.value = '';
+            positionInput.value = '';
+            await loadRepositories();
+        } catch (e) { showError(e); }
     });
 
     document.querySelector('.modal-close')?.addEventListener('click', () => {
@@ -218,15 +264,36 @@ function initializeEventListeners() {
         });
     });
 
-    document.querySelectorAll('.filter-btn').forEach(btn => {
+    document.querySelectorAll('.filter-btn[data-filter]').forEach(btn => {
         btn.addEventListener('click', (e) => {
-            document.querySelectorAll('.filter-btn').forEach(b => b.classList.remove('active'));
+            document.querySelectorAll('.filter-btn[data-filter]').forEach(b => b.classList.remove('active'));
             e.currentTarget.classList.add('active');
             currentFilter = e.currentTarget.dataset.filter;
             filterAndRender();
         });
     });
 
+    document.getElementById('upgrade-all-btn')?.addEventListener('click', async () => {
+        if (!upgradablePackageNames.length || !confirm(`${i18n.t('upgrade_all')} (${upgradablePackageNames.length})?`)) return;
+        try {
+            showLoading(true);
+            const result = await withRetry(() => invoke('upgrade_all'));
+            // Sabitli paketleri değiştireceği için atlanan yükseltmeler de gösterilir
+            const failed = result?.results.filter(r => r.status === 'Failed' || (r.status === 'Skipped' && r.error?.kind !== 'Cancelled')) ?? [];
+            if (failed.length) alert(failed.map(r => `${r.package}: ${formatError(r.error)}`).join('\n'));
+            await refreshData();
+        } catch (e) { showError(e); }
+        finally { showLoading(false); }
+    });
+
+    document.getElementById('cancel-operation')?.addEventListener('click', async () => {
+        if (!currentOperationId) return;
+        try {
+            const report = await invoke('cancel_operation', { operationId: currentOperationId });
+            if (!report.consistent) alert(report.message);
+        } catch (e) { console.error('Cancel failed:', e); }
+    });
+
     document.getElementById('close-details')?.addEventListener('click', () => {
         if (elements.detailsPanel) elements.detailsPanel.style.display = 'none';
         elements.mainContainer?.classList.remove('has-details');
@@ -259,6 +326,8 @@ function filterAndRender() {
             return installedPackageNames.includes(pkg.name);
         } else if (filterStr === 'updates') {
             return upgradablePackageNames.includes(pkg.name);
+        } else if (filterStr === 'held') {
+            return heldPackageNames.includes(pkg.name);
         } else if (filterStr === 'available') {
             return !installedPackageNames.includes(pkg.name);
         }
@@ -362,52 +431,208 @@ function selectPackage(pkg) {
         if (elements.packageDetails) {
             const isInstalled = installedPackageNames.includes(pkg.name);
             const hasUpdate = upgradablePackageNames.includes(pkg.name);
+            const isHeld = heldPackageNames.includes(pkg.name);
 
             elements.packageDetails.innerHTML = `
                 <h2>${pkg.name}</h2>
                 <div class="package-meta">
                     <span><strong>${i18n.t('version')}:</strong> ${pkg.version}</span>
                     <span><strong>${i18n.t('category')}:</strong> ${pkg.part_of}</span>
+                    <span><strong>${i18n.t('repository')}:</strong> ${pkg.repository}</span>
                 </div>
                 <p class="package-description">${pkg.description || pkg.summary}</p>
+                ${pkg.dependencies?.length ? `<p class="package-relations"><strong>${i18n.t('dependencies')}:</strong> ${pkg.dependencies.map(formatDependency).join(', ')}</p>` : ''}
+                ${pkg.conflicts?.length ? `<p class="package-relations"><strong>${i18n.t('conflicts')}:</strong> ${pkg.conflicts.map(formatDependency).join(', ')}</p>` : ''}
+                ${pkg.replaces?.length ? `<p class="package-relations"><strong>${i18n.t('replaces')}:</strong> ${pkg.replaces.map(d => d.name).join(', ')}</p>` : ''}
+                ${pkg.packager ? `<p class="package-relations"><strong>${i18n.t('packager')}:</strong> ${pkg.packager.name} &lt;${pkg.packager.email}&gt;</p>` : ''}
                 <div class="detail-actions">
                     ${!isInstalled ? `<button class="btn-install" id="action-install">📥 ${i18n.t('install')}</button>` : ''}
                     ${isInstalled ? `<button class="btn-remove" id="action-remove">🗑️ ${i18n.t('remove')}</button>` : ''}
                     ${hasUpdate ? `<button class="btn-update" id="action-update">🔄 ${i18n.t('update')}</button>` : ''}
+                    ${isInstalled ? `<button class="btn-hold" id="action-hold">📌 ${i18n.t(isHeld ? 'unhold' : 'hold')}</button>` : ''}
                 </div>
             `;
 
             // Butonlara event listener ekle
             document.getElementById('action-install')?.addEventListener('click', async () => {
                 try {
+                    const plan = await invoke('get_install_plan', { packageNames: [pkg.name] });
+                    if (!confirm(formatPlan(plan))) return;
                     showLoading(true);
-                    await invoke('install_package', { packageName: pkg.name });
+                    await withRetry(() => invoke('install_package', { packageName: pkg.name }));
                     await refreshData();
-                } catch (e) { alert(e); }
+                } catch (e) { showError(e); }
                 finally { showLoading(false); }
             });
 
             document.getElementById('action-remove')?.addEventListener('click', async () => {
                 try {
+                    const dependents = await invoke('get_reverse_dependencies', {
+                        packageName: pkg.name, installedOnly: true, recursive: true
+                    });
+                    if (dependents.length && !confirm(`${i18n.t('remove_breaks')}\n\n${dependents.map(d => `  • ${d.name}`).join('\n')}\n\n${i18n.t('plan_confirm')}`)) return;
                     showLoading(true);
-                    await invoke('remove_package', { packageName: pkg.name });
+                    await withRetry(() => invoke('remove_package', { packageName: pkg.name, force: dependents.length > 0 }));
                     await refreshData();
-                } catch (e) { alert(e); }
+                } catch (e) { showError(e); }
                 finally { showLoading(false); }
             });
 
+            document.getElementById('action-hold')?.addEventListener('click', async () => {
+                try {
+                    heldPackageNames = await invoke(isHeld ? 'unhold_package' : 'hold_package', { packageName: pkg.name });
+                    await refreshData();
+                    selectPackage(pkg);
+                } catch (e) { showError(e); }
+            });
+
             document.getElementById('action-update')?.addEventListener('click', async () => {
                 try {
                     showLoading(true);
-                    await invoke('update_package', { packageName: pkg.name });
+                    await withRetry(() => invoke('update_package', { packageName: pkg.name }));
                     await refreshData();
-                } catch (e) { alert(e); }
+                } catch (e) { showError(e); }
                 finally { showLoading(false); }
             });
         }
     }
 }
 
+// Ayarlar penceresindeki depo listesini pisi'den okuyup çiz
+const TRUST_ICONS = {
+    signed: 'fa-shield',
+    checksum_only: 'fa-check',
+    missing_signature: 'fa-exclamation-triangle',
+    unverified: 'fa-question-circle',
+    checksum_mismatch: 'fa-exclamation-triangle',
+    bad_signature: 'fa-exclamation-triangle',
+};
+
+function trustBadge(trust) {
+    if (!trust) return '';
+    const rejected = ['checksum_mismatch', 'missing_signature', 'bad_signature'].includes(trust.status);
+    const detail = trust.message || (trust.expected ? `${i18n.t('expected')}: ${trust.expected}` : '');
+    return `<span class="repo-trust ${rejected ? 'rejected' : trust.status}" title="${detail}">
+        <i class="fa ${TRUST_ICONS[trust.status]}"></i> ${i18n.t(`trust_${trust.status}`)}</span>`;
+}
+
+async function loadRepositories() {
+    const list = document.getElementById('repo-list');
+    if (!list) return;
+    try {
+        const repos = await invoke('list_repositories');
+        // Yansı listesi okunamazsa sadece geçiş düğmeleri gizlenir
+        const mirrors = await invoke('list_mirrors').catch(() => []);
+        const hasMirrors = name => mirrors.some(m => m.repository === name);
+        list.innerHTML = repos.map(repo => `
+            <li class="repo-item ${repo.enabled ? '' : 'disabled'}" data-repo="${repo.name}">
+                <input type="checkbox" class="repo-toggle" ${repo.enabled ? 'checked' : ''}>
+                <div class="repo-info">
+                    <strong>${repo.priority + 1}. ${repo.name}</strong>
+                    <div class="repo-url" title="${repo.url}">${repo.url}</div>
+                    <div class="repo-meta">
+                        ${i18n.t('last_sync')}: ${repo.last_update ? new Date(repo.last_update * 1000).toLocaleString() : i18n.t('never')}
+                        ${repo.package_count != null ? ` · ${repo.package_count} ${i18n.t('packages')}` : ''}
+                        ${trustBadge(repo.trust)}
+                    </div>
+                </div>
+                <button class="repo-up" title="${i18n.t('move_up')}" ${repo.priority === 0 ? 'disabled' : ''}><i class="fa fa-arrow-up"></i></button>
+                <button class="repo-down" title="${i18n.t('move_down')}" ${repo.priority === repos.length - 1 ? 'disabled' : ''}><i class="fa fa-arrow-down"></i></button>
+                <button class="repo-update" title="${i18n.t('update_repository')}"><i class="fa fa-refresh"></i></button>
+                ${hasMirrors(repo.name) ? `<button class="repo-mirror" title="${i18n.t('use_fastest_mirror')}"><i class="fa fa-tachometer"></i></button>` : ''}
+                <button class="repo-remove" title="${i18n.t('remove_repository')}"><i class="fa fa-trash"></i></button>
+            </li>
+        `).join('');
+    } catch (e) {
+        showError(e);
+        return;
+    }
+
+    list.querySelectorAll('.repo-item').forEach(item => {
+        const name = item.dataset.repo;
+        const run = async (command, args = {}) => {
+            try {
+                await withRetry(() => invoke(command, { name, ...args }));
+                await loadRepositories();
+                await refreshData();
+            } catch (e) { showError(e); }
+        };
+        item.querySelector('.repo-toggle').addEventListener('change', (e) =>
+            run(e.target.checked ? 'enable_repository' : 'disable_repository'));
+        item.querySelector('.repo-up').addEventListener('click', () => run('move_repository', { direction: 'Up' }));
+        item.querySelector('.repo-down').addEventListener('click', () => run('move_repository', { direction: 'Down' }));
+        item.querySelector('.repo-update').addEventListener('click', () => run('update_repositories'));
+        item.querySelector('.repo-mirror')?.addEventListener('click', () => {
+            if (confirm(`${i18n.t('use_fastest_mirror')}: ${name}?`)) run('use_fastest_mirror');
+        });
+        item.querySelector('.repo-remove').addEventListener('click', () => {
+            if (confirm(`${i18n.t('remove_repository')}: ${name}?`)) run('remove_repository');
+        });
+    });
+}
+
+// Yeniden denenebilecek (geçici) hata türleri; PmError::is_retryable ile aynı olmalı
+const RETRYABLE_ERRORS = ['LockHeld', 'NetworkUnavailable', 'ChecksumMismatch'];
+
+// Backend'in döndürdüğü PmError'u yerelleştirilmiş mesaja çevir; pisi çıktısı ayrıntı olarak eklenir
+function formatError(error) {
+    if (!error || typeof error !== 'object') return String(error);
+    const key = `error_${error.kind}`;
+    const title = i18n.t(key) === key ? '' : i18n.t(key);
+    return [title, error.message].filter(Boolean).join('\n\n') || error.kind;
+}
+
+function showError(error) {
+    // Kullanıcının kendi iptal ettiği işlem için uyarı gösterme
+    if (error?.kind === 'Cancelled') return;
+    alert(formatError(error));
+}
+
+// İşlemi çalıştırır; geçici bir hatada kullanıcıya yeniden denemeyi sorar.
+// Kullanıcı yeniden denemek istemezse null döner, diğer hatalar fırlatılır.
+async function withRetry(action) {
+    for (;;) {
+        try {
+            return await action();
+        } catch (e) {
+            if (!RETRYABLE_ERRORS.includes(e?.kind)) throw e;
+            if (!confirm(`${formatError(e)}\n\n${i18n.t('retry_question')}`)) return null;
+        }
+    }
+}
+
+// Kurulum planını onay penceresi için metne çevir
+function formatPlan(plan) {
+    const list = (items) => items.map(p => `  • ${p.name} ${p.version}-${p.release}`).join('\n');
+    const lines = [];
+    if (plan.install.length) lines.push(`${i18n.t('plan_install')} (${plan.install.length}):\n${list(plan.install)}`);
+    if (plan.upgrade.length) lines.push(`${i18n.t('plan_upgrade')} (${plan.upgrade.length}):\n${list(plan.upgrade)}`);
+    if (plan.remove.length) lines.push(`${i18n.t('plan_remove')} (${plan.remove.length}):\n${list(plan.remove)}`);
+    lines.push(`${i18n.t('download_size')}: ${formatSize(plan.download_size)}`);
+    lines.push(`${i18n.t('installed_size')}: ${formatSize(plan.installed_size)}`);
+    lines.push(i18n.t('plan_confirm'));
+    return lines.join('\n\n');
+}
+
+function formatSize(bytes) {
+    if (bytes >= 1073741824) return `${(bytes / 1073741824).toFixed(1)} GB`;
+    if (bytes >= 1048576) return `${(bytes / 1048576).toFixed(1)} MB`;
+    return `${Math.ceil(bytes / 1024)} KB`;
+}
+
+// "glibc (>= 2.30, <= 2.40)" biçiminde bağımlılık metni
+function formatDependency(dep) {
+    const c = dep.constraint || {};
+    const parts = [];
+    if (c.version) parts.push(`= ${c.version}`);
+    if (c.version_from) parts.push(`>= ${c.version_from}`);
+    if (c.version_to) parts.push(`<= ${c.version_to}`);
+    if (c.release != null) parts.push(`release = ${c.release}`);
+    if (c.release_from != null) parts.push(`release >= ${c.release_from}`);
+    if (c.release_to != null) parts.push(`release <= ${c.release_to}`);
+    return parts.length ? `${dep.name} (${parts.join(', ')})` : dep.name;
+}
+
 function renderComponents(components) {
     if (!elements.componentsList) return;
 
@@ -436,8 +661,34 @@ function renderComponents(components) {
     });
 }
 
+// Backend'den gelen OperationProgress olayını ilerleme çubuğunda göster
+function renderProgress(progress) {
+    if (!elements.operationProgress) return;
+
+    if (['Finished', 'Failed', 'Cancelled'].includes(progress.phase)) {
+        currentOperationId = null;
+        elements.operationProgress.style.display = 'none';
+        return;
+    }
+
+    currentOperationId = progress.operation_id;
+
+    elements.operationProgress.style.display = 'block';
+    let percent = progress.percent ?? 0;
+    if (progress.step && progress.total_steps) {
+        percent = Math.round(((progress.step - 1) / progress.total_steps) * 100);
+    }
+    if (elements.progressFill && (progress.percent != null || progress.step)) {
+        elements.progressFill.style.width = `${percent}%`;
+    }
+    if (elements.progressText) {
+        elements.progressText.textContent = progress.message;
+    }
+}
+
 function showLoading(show) {
     if (elements.loading) elements.loading.style.display = show ? 'flex' : 'none';
+    if (!show && elements.operationProgress) elements.operationProgress.style.display = 'none';
     if (elements.packagesGrid) elements.packagesGrid.style.display = show ? 'none' : 'grid';
 }
 
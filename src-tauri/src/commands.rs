@@ -1,7 +1,19 @@
 // src-tauri/src/commands.rs
 use serde::{Serialize, Deserialize};
-use pm_core::backend::package_manager;
+use tauri::State;
+use pm_core::backend::package_manager::{self, RunnerState};
+use pm_core::backend::actions::UpgradablePackage;
 use pm_core::backend::xml_parser::{PackageInfo, Component};
+use pm_core::backend::installed_db::{InstalledFile, InstalledPackage};
+use pm_core::backend::resolver::TransactionPlan;
+use pm_core::backend::reverse_deps::ReverseDependency;
+use pm_core::backend::operation::{CancelReport, OperationRegistry, RunningOperation};
+use pm_core::backend::names::{PackageName, RepoName};
+use pm_core::backend::repository::{MoveDirection, RepositoryInfo};
+use pm_core::backend::repo_probe::RepositoryProbe;
+use pm_core::backend::mirrors::{Mirror, MirrorReport};
+use pm_core::backend::error::PmError;
+use pm_core::backend::transaction::{TransactionAction, TransactionResult};
 
 #[derive(Serialize, Deserialize, Debug)]
 pub struct PackageStats {
@@ -12,15 +24,19 @@ pub struct PackageStats {
 }
 
 #[tauri::command]
-pub async fn get_package_stats() -> Result<PackageStats, String> {
+pub async fn get_package_stats(
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+) -> Result<PackageStats, PmError> {
     let packages = package_manager::get_packages().await?;
     let installed = package_manager::get_installed_packages().await?;
-    let updates = package_manager::get_upgradable_packages().await?;
+    let updates = package_manager::get_upgradable_packages(runner, registry).await?;
     
     let total_count = packages.len();
     let installed_count = installed.len();
     let available_count = if total_count > installed_count { total_count - installed_count } else { 0 };
-    let updates_count = updates.len();
+    // Sabitlenmiş paketler bekleyen güncelleme sayılmaz
+    let updates_count = updates.iter().filter(|p| !p.held).count();
 
     Ok(PackageStats {
         total_count,
@@ -31,51 +47,212 @@ pub async fn get_package_stats() -> Result<PackageStats, String> {
 }
 
 #[tauri::command]
-pub async fn get_upgradable_packages() -> Result<Vec<String>, String> {
-    package_manager::get_upgradable_packages().await
+pub async fn get_upgradable_packages(
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+) -> Result<Vec<UpgradablePackage>, PmError> {
+    package_manager::get_upgradable_packages(runner, registry).await
 }
 
 #[tauri::command]
-pub async fn get_packages() -> Result<Vec<PackageInfo>, String> {
+pub async fn upgrade_all(
+    app: tauri::AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+) -> Result<TransactionResult, PmError> {
+    package_manager::upgrade_all(app, runner, registry).await
+}
+
+#[tauri::command]
+pub async fn get_held_packages() -> Result<Vec<String>, PmError> {
+    package_manager::get_held_packages().await
+}
+
+#[tauri::command]
+pub async fn hold_package(package_name: PackageName) -> Result<Vec<String>, PmError> {
+    package_manager::hold_package(package_name).await
+}
+
+#[tauri::command]
+pub async fn unhold_package(package_name: PackageName) -> Result<Vec<String>, PmError> {
+    package_manager::unhold_package(package_name).await
+}
+
+#[tauri::command]
+pub async fn get_packages() -> Result<Vec<PackageInfo>, PmError> {
     package_manager::get_packages().await
 }
 
 #[tauri::command]
-pub async fn get_components() -> Result<Vec<Component>, String> {
+pub async fn get_components() -> Result<Vec<Component>, PmError> {
     package_manager::get_components().await
 }
 
 #[tauri::command]
-pub async fn install_package(package_name: String) -> Result<String, String> {
-    package_manager::install_package(package_name).await
+pub async fn get_install_plan(package_names: Vec<PackageName>) -> Result<TransactionPlan, PmError> {
+    package_manager::get_install_plan(package_names).await
+}
+
+#[tauri::command]
+pub async fn install_package(
+    app: tauri::AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+    package_name: PackageName,
+) -> Result<String, PmError> {
+    package_manager::install_package(app, runner, registry, package_name).await
+}
+
+#[tauri::command]
+pub async fn remove_package(
+    app: tauri::AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+    package_name: PackageName,
+    force: Option<bool>,
+) -> Result<String, PmError> {
+    package_manager::remove_package(app, runner, registry, package_name, force).await
 }
 
 #[tauri::command]
-pub async fn remove_package(package_name: String) -> Result<String, String> {
-    package_manager::remove_package(package_name).await
+pub async fn get_reverse_dependencies(
+    package_name: PackageName,
+    installed_only: bool,
+    recursive: bool,
+) -> Result<Vec<ReverseDependency>, PmError> {
+    package_manager::get_reverse_dependencies(package_name, installed_only, recursive).await
 }
 
 #[tauri::command]
-pub async fn update_package(package_name: String) -> Result<String, String> {
-    package_manager::update_package(package_name).await
+pub async fn update_package(
+    app: tauri::AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+    package_name: PackageName,
+) -> Result<String, PmError> {
+    package_manager::update_package(app, runner, registry, package_name).await
 }
 
 #[tauri::command]
-pub async fn search_packages(query: String) -> Result<Vec<PackageInfo>, String> {
+pub async fn run_transaction(
+    app: tauri::AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+    actions: Vec<TransactionAction>,
+    force: Option<bool>,
+) -> Result<TransactionResult, PmError> {
+    package_manager::run_transaction(app, runner, registry, actions, force).await
+}
+
+#[tauri::command]
+pub async fn search_packages(query: String) -> Result<Vec<PackageInfo>, PmError> {
     package_manager::search_packages(query).await
 }
 
 #[tauri::command]
-pub async fn update_repo() -> Result<(), String> {
-    package_manager::update_repo().await
+pub async fn preview_index_file(path: String) -> Result<Vec<PackageInfo>, PmError> {
+    package_manager::preview_index_file(path).await
+}
+
+#[tauri::command]
+pub async fn update_repo(
+    app: tauri::AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+) -> Result<(), PmError> {
+    package_manager::update_repo(app, runner, registry).await
+}
+
+#[tauri::command]
+pub async fn list_repositories(runner: RunnerState<'_>) -> Result<Vec<RepositoryInfo>, PmError> {
+    package_manager::list_repositories(runner).await
 }
 
 #[tauri::command]
-pub async fn get_package_icon(icon_name: String) -> Result<String, String> {
+pub async fn probe_repository(url: String) -> Result<RepositoryProbe, PmError> {
+    package_manager::probe_repository(url).await
+}
+
+#[tauri::command]
+pub async fn add_repository(
+    runner: RunnerState<'_>,
+    name: RepoName,
+    url: String,
+    position: Option<usize>,
+) -> Result<(), PmError> {
+    package_manager::add_repository(runner, name, url, position).await
+}
+
+#[tauri::command]
+pub async fn move_repository(
+    runner: RunnerState<'_>,
+    name: RepoName,
+    direction: MoveDirection,
+) -> Result<Vec<RepositoryInfo>, PmError> {
+    package_manager::move_repository(runner, name, direction).await
+}
+
+#[tauri::command]
+pub async fn remove_repository(runner: RunnerState<'_>, name: RepoName) -> Result<(), PmError> {
+    package_manager::remove_repository(runner, name).await
+}
+
+#[tauri::command]
+pub async fn enable_repository(runner: RunnerState<'_>, name: RepoName) -> Result<(), PmError> {
+    package_manager::enable_repository(runner, name).await
+}
+
+#[tauri::command]
+pub async fn disable_repository(runner: RunnerState<'_>, name: RepoName) -> Result<(), PmError> {
+    package_manager::disable_repository(runner, name).await
+}
+
+#[tauri::command]
+pub async fn test_mirrors(force: Option<bool>) -> Result<MirrorReport, PmError> {
+    package_manager::test_mirrors(force).await
+}
+
+#[tauri::command]
+pub async fn list_mirrors() -> Result<Vec<Mirror>, PmError> {
+    package_manager::list_mirrors().await
+}
+
+#[tauri::command]
+pub async fn use_fastest_mirror(runner: RunnerState<'_>, name: RepoName) -> Result<Vec<RepositoryInfo>, PmError> {
+    package_manager::use_fastest_mirror(runner, name).await
+}
+
+#[tauri::command]
+pub async fn update_repositories(runner: RunnerState<'_>, name: Option<RepoName>) -> Result<(), PmError> {
+    package_manager::update_repositories(runner, name).await
+}
+
+#[tauri::command]
+pub async fn get_running_operations(registry: State<'_, OperationRegistry>) -> Result<Vec<RunningOperation>, PmError> {
+    package_manager::get_running_operations(registry).await
+}
+
+#[tauri::command]
+pub async fn cancel_operation(registry: State<'_, OperationRegistry>, operation_id: String) -> Result<CancelReport, PmError> {
+    package_manager::cancel_operation(registry, operation_id).await
+}
+
+#[tauri::command]
+pub async fn get_package_icon(icon_name: String) -> Result<String, PmError> {
     package_manager::get_package_icon(icon_name).await
 }
 
 #[tauri::command]
-pub async fn get_installed_packages() -> Result<Vec<String>, String> {
+pub async fn get_installed_packages() -> Result<Vec<String>, PmError> {
     package_manager::get_installed_packages().await
+}
+
+#[tauri::command]
+pub async fn get_installed_package_details() -> Result<Vec<InstalledPackage>, PmError> {
+    package_manager::get_installed_package_details().await
+}
+
+#[tauri::command]
+pub async fn get_installed_files(package_name: PackageName) -> Result<Vec<InstalledFile>, PmError> {
+    package_manager::get_installed_files(package_name).await
 }
\ No newline at end of file
@@ -1,20 +1,48 @@
 // src-tauri/src/lib.rs
 mod commands;
 
+use pm_core::backend::operation::OperationRegistry;
+use pm_core::backend::runner::{PisiRunner, SystemPisiRunner};
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
   tauri::Builder::default()
     .plugin(tauri_plugin_shell::init())
+    .manage(OperationRegistry::default())
+    .manage(Box::new(SystemPisiRunner::default()) as Box<dyn PisiRunner>)
     .invoke_handler(tauri::generate_handler![
+        commands::get_install_plan,
         commands::install_package,
         commands::remove_package,
+        commands::get_reverse_dependencies,
         commands::update_package,
+        commands::run_transaction,
         commands::get_packages,
         commands::get_components,
         commands::search_packages,
+        commands::preview_index_file,
         commands::get_installed_packages,
+        commands::get_installed_package_details,
+        commands::get_installed_files,
         commands::get_upgradable_packages,
+        commands::upgrade_all,
+        commands::get_held_packages,
+        commands::hold_package,
+        commands::unhold_package,
         commands::update_repo,
+        commands::list_repositories,
+        commands::probe_repository,
+        commands::add_repository,
+        commands::move_repository,
+        commands::remove_repository,
+        commands::enable_repository,
+        commands::disable_repository,
+        commands::update_repositories,
+        commands::test_mirrors,
+        commands::list_mirrors,
+        commands::use_fastest_mirror,
+        commands::get_running_operations,
+        commands::cancel_operation,
         commands::get_package_icon,
         commands::get_package_stats
     ])
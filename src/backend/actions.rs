@@ -0,0 +1,249 @@
+use serde::{Serialize, Deserialize};
+use crate::backend::error::PmError;
+use crate::backend::installed_db::InstalledDb;
+use crate::backend::names::PackageName;
+use crate::backend::operation::{next_operation_id, run_pisi_with_progress, OperationProgress, OperationRegistry};
+use crate::backend::privileged::pisi_args;
+use crate::backend::resolver::Resolver;
+use crate::backend::reverse_deps::ReverseDependencyMap;
+use crate::backend::runner::PisiRunner;
+use crate::backend::transaction::{
+    self, PackageResult, PackageResultStatus, TransactionAction, TransactionActionKind, TransactionResult,
+};
+use crate::backend::xml_parser::{PackageInfo, XmlParser};
+
+/// `pisi list-upgrades` çıktısındaki bir paket
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct UpgradablePackage {
+    pub name: String,
+    /// Kullanıcı sabitlediği için `upgrade_all` bu paketi atlar
+    pub held: bool,
+}
+
+/// pisi çalıştıran paket işlemleri. Tauri komutları bunu managed state'teki runner ile,
+/// testler `ScriptedPisiRunner` ile kurar.
+pub struct PackageActions<'a> {
+    runner: &'a dyn PisiRunner,
+    registry: &'a OperationRegistry,
+    installed: InstalledDb,
+}
+
+impl<'a> PackageActions<'a> {
+    pub fn new(runner: &'a dyn PisiRunner, registry: &'a OperationRegistry, installed: InstalledDb) -> Self {
+        Self { runner, registry, installed }
+    }
+
+    pub async fn install(
+        &self,
+        package_name: &PackageName,
+        on_progress: impl FnMut(OperationProgress) + Send,
+    ) -> Result<String, PmError> {
+        let operation_id = next_operation_id("install");
+        let args = pisi_args("it", &["-y"], &[package_name.as_str()]);
+        run_pisi_with_progress(self.runner, self.registry, &operation_id, &args, on_progress)
+            .await?;
+
+        Ok(format!("Package {} installed successfully", package_name))
+    }
+
+    /// Paketi kaldırır. Kurulu başka paketler bu pakete bağımlıysa `force` verilmedikçe
+    /// kaldırmayı reddeder ve etkilenecek paketleri hata mesajında listeler.
+    pub async fn remove(
+        &self,
+        package_name: &PackageName,
+        force: bool,
+        on_progress: impl FnMut(OperationProgress) + Send,
+    ) -> Result<String, PmError> {
+        if !force {
+            self.check_removal(&[package_name.as_str()])?;
+        }
+
+        let operation_id = next_operation_id("remove");
+        let args = pisi_args("rm", &["-y"], &[package_name.as_str()]);
+        run_pisi_with_progress(self.runner, self.registry, &operation_id, &args, on_progress)
+            .await?;
+
+        Ok(format!("Package {} removed successfully", package_name))
+    }
+
+    pub async fn update(
+        &self,
+        package_name: &PackageName,
+        on_progress: impl FnMut(OperationProgress) + Send,
+    ) -> Result<String, PmError> {
+        let operation_id = next_operation_id("update");
+        let args = pisi_args("up", &["-y"], &[package_name.as_str()]);
+        run_pisi_with_progress(self.runner, self.registry, &operation_id, &args, on_progress)
+            .await?;
+
+        Ok(format!("Package {} updated successfully", package_name))
+    }
+
+    /// Birden fazla kurma/kaldırma/yükseltme işlemini tek seferde uygular ve paket başına sonuç döner.
+    /// Kaldırılacak paketler, `force` verilmedikçe `remove` ile aynı ters bağımlılık
+    /// kontrolünden geçer (aynı işlemde birlikte kaldırılan bağımlılar sorun sayılmaz).
+    pub async fn run_transaction(
+        &self,
+        actions: &[TransactionAction],
+        force: bool,
+        on_progress: impl FnMut(OperationProgress) + Send,
+    ) -> Result<TransactionResult, PmError> {
+        transaction::validate_actions(actions)?;
+
+        let removed: Vec<&str> = actions
+            .iter()
+            .filter(|a| a.kind == TransactionActionKind::Remove)
+            .map(|a| a.package.as_str())
+            .collect();
+        if !removed.is_empty() && !force {
+            self.check_removal(&removed)?;
+        }
+
+        let operation_id = next_operation_id("transaction");
+        Ok(transaction::run_actions(self.runner, self.registry, &operation_id, actions, on_progress).await)
+    }
+
+    /// Kaldırılacak paketlere bağımlı, kendisi kaldırılmayan kurulu paket varsa hata döner
+    fn check_removal(&self, removed: &[&str]) -> Result<(), PmError> {
+        let installed = self.installed.list()?;
+        let map = ReverseDependencyMap::from_installed(&installed);
+        let mut broken: Vec<String> = removed
+            .iter()
+            .flat_map(|name| map.query(name, true, true))
+            .map(|d| d.name)
+            .filter(|name| !removed.contains(&name.as_str()))
+            .collect();
+        broken.sort();
+        broken.dedup();
+
+        if broken.is_empty() {
+            Ok(())
+        } else {
+            Err(PmError::DependencyConflict {
+                message: format!(
+                    "Removing {} would break installed packages: {}",
+                    removed.join(", "),
+                    broken.join(", ")
+                ),
+            })
+        }
+    }
+
+    /// Yükseltilebilir paketler; `held` listesindekiler işaretlenir
+    pub async fn upgradable(&self, held: &[String]) -> Result<Vec<UpgradablePackage>, PmError> {
+        Ok(self
+            .list_upgrades()
+            .await?
+            .into_iter()
+            .map(|name| UpgradablePackage { held: held.contains(&name), name })
+            .collect())
+    }
+
+    async fn list_upgrades(&self) -> Result<Vec<String>, PmError> {
+        let output = self.runner.output(&["list-upgrades"]).await?;
+
+        if output.is_success() {
+            Ok(output
+                .stdout
+                .lines()
+                .map(|line| line.split_whitespace().next().unwrap_or("").to_string())
+                .filter(|s| !s.is_empty())
+                .collect())
+        } else {
+            Err(PmError::from_pisi_output(&output))
+        }
+    }
+
+    /// Sabitlenmiş paketler dışındaki tüm yükseltmeleri uygular.
+    /// Sabitli paket yoksa da paketler açıkça listelenir ki sonuç paket başına raporlanabilsin.
+    ///
+    /// pisi bir paketi yükseltirken bağımlılıklarını da yükseltir. Bu yüzden her paket
+    /// `index` üzerinde çözülür; sabitli bir paketi kuracak, yükseltecek veya kaldıracak
+    /// yükseltmeler çalıştırılmaz ve nedeniyle birlikte `Skipped` olarak raporlanır.
+    pub async fn upgrade_all(
+        &self,
+        index: &[PackageInfo],
+        held: &[String],
+        on_progress: impl FnMut(OperationProgress) + Send,
+    ) -> Result<TransactionResult, PmError> {
+        let packages: Vec<PackageName> = self
+            .list_upgrades()
+            .await?
+            .into_iter()
+            .filter(|name| !held.contains(name))
+            .filter_map(|name| match PackageName::new(name) {
+                Ok(package) => Some(package),
+                Err(e) => {
+                    println!("Skipping upgrade: {}", e);
+                    None
+                }
+            })
+            .collect();
+
+        let mut blocked = Vec::new();
+        let actions: Vec<TransactionAction> = if held.is_empty() {
+            packages.into_iter().map(|package| TransactionAction { kind: TransactionActionKind::Upgrade, package }).collect()
+        } else {
+            let installed = self.installed.list()?;
+            let resolver = Resolver::new(index, &installed);
+            packages
+                .into_iter()
+                .filter(|package| match held_in_plan(&resolver, package, held) {
+                    Some(error) => {
+                        blocked.push(PackageResult {
+                            package: package.to_string(),
+                            kind: TransactionActionKind::Upgrade,
+                            status: PackageResultStatus::Skipped,
+                            error: Some(error),
+                        });
+                        false
+                    }
+                    None => true,
+                })
+                .map(|package| TransactionAction { kind: TransactionActionKind::Upgrade, package })
+                .collect()
+        };
+
+        let operation_id = next_operation_id("upgrade-all");
+        let mut result = transaction::run_actions(self.runner, self.registry, &operation_id, &actions, on_progress).await;
+        result.results.extend(blocked);
+        Ok(result)
+    }
+
+    /// Tüm depo indekslerini günceller. Başarılı olursa RAM önbelleği boşaltılır; bir sonraki
+    /// yüklemede değişen depolar içerik özetinden tanınıp yeniden parse edilir.
+    pub async fn update_repo(&self, on_progress: impl FnMut(OperationProgress) + Send) -> Result<(), PmError> {
+        let operation_id = next_operation_id("update-repo");
+        run_pisi_with_progress(self.runner, self.registry, &operation_id, &["ur"], on_progress).await?;
+        XmlParser::clear_memory_cache();
+        Ok(())
+    }
+}
+
+/// Paketin yükseltme planı sabitli paketlere dokunuyorsa nedenini döner.
+/// Plan çıkarılamazsa (ör. paket yerel indekste yoksa) karar pisi'ye bırakılır.
+fn held_in_plan(resolver: &Resolver, package: &PackageName, held: &[String]) -> Option<PmError> {
+    let plan = match resolver.plan_install(std::slice::from_ref(package)) {
+        Ok(plan) => plan,
+        Err(e) => {
+            println!("Could not plan upgrade of {}: {}", package, e);
+            return None;
+        }
+    };
+    let mut touched: Vec<&str> = plan
+        .install
+        .iter()
+        .chain(&plan.upgrade)
+        .chain(&plan.remove)
+        .map(|p| p.name.as_str())
+        .filter(|name| held.iter().any(|h| h == name))
+        .collect();
+    if touched.is_empty() {
+        return None;
+    }
+    touched.sort();
+    touched.dedup();
+    Some(PmError::DependencyConflict {
+        message: format!("Upgrading {} would also change held packages: {}", package, touched.join(", ")),
+    })
+}
@@ -0,0 +1,90 @@
+use anyhow::Result;
+use std::fs::{self, File};
+use std::io::{BufRead, BufReader, Read};
+use std::path::{Path, PathBuf};
+use crate::backend::error::PmError;
+
+/// Önizleme için açılabilecek indeks dosyası uzantıları
+pub const INDEX_FILE_EXTENSIONS: [&str; 4] = ["xml", "xz", "zst", "gz"];
+
+/// Depo indekslerinin sıkıştırma biçimi
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IndexCompression {
+    None,
+    Xz,
+    Zstd,
+    Gzip,
+}
+
+impl IndexCompression {
+    const XZ_MAGIC: &'static [u8] = &[0xFD, b'7', b'z', b'X', b'Z', 0x00];
+    const ZSTD_MAGIC: &'static [u8] = &[0x28, 0xB5, 0x2F, 0xFD];
+    const GZIP_MAGIC: &'static [u8] = &[0x1F, 0x8B];
+
+    /// Dosyanın ilk baytlarına bakarak biçimi tespit eder
+    pub fn from_magic(header: &[u8]) -> Option<Self> {
+        if header.starts_with(Self::XZ_MAGIC) {
+            Some(Self::Xz)
+        } else if header.starts_with(Self::ZSTD_MAGIC) {
+            Some(Self::Zstd)
+        } else if header.starts_with(Self::GZIP_MAGIC) {
+            Some(Self::Gzip)
+        } else {
+            None
+        }
+    }
+
+    /// Dosya uzantısına bakarak biçimi tespit eder (`.xz`, `.zst`, `.gz`)
+    pub fn from_extension(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("xz") => Self::Xz,
+            Some("zst") | Some("zstd") => Self::Zstd,
+            Some("gz") => Self::Gzip,
+            _ => Self::None,
+        }
+    }
+
+    /// İlgili açıcıyı okuyucunun üzerine sarar
+    pub fn wrap<'a, R: BufRead + 'a>(self, reader: R) -> Result<Box<dyn BufRead + 'a>> {
+        Ok(match self {
+            Self::None => Box::new(reader),
+            Self::Xz => Box::new(BufReader::new(xz2::bufread::XzDecoder::new(reader))),
+            Self::Zstd => Box::new(BufReader::new(zstd::stream::read::Decoder::with_buffer(reader)?)),
+            Self::Gzip => Box::new(BufReader::new(flate2::bufread::GzDecoder::new(reader))),
+        })
+    }
+}
+
+/// Sıkıştırılmış ya da düz bir okuyucuyu açar.
+/// Biçim önce magic byte'lardan, bulunamazsa `hint` ile verilen uzantıdan belirlenir.
+pub fn open_index_reader<'a, R: Read + 'a>(reader: R, hint: IndexCompression) -> Result<Box<dyn BufRead + 'a>> {
+    let mut reader = BufReader::new(reader);
+    let compression = IndexCompression::from_magic(reader.fill_buf()?).unwrap_or(hint);
+    compression.wrap(reader)
+}
+
+/// `pisi-index.xml`, `.xz`, `.zst` veya `.gz` indeks dosyasını açıp düz XML akışı döner
+pub fn open_index(path: &Path) -> Result<Box<dyn BufRead>> {
+    let file = File::open(path)
+        .map_err(|e| anyhow::anyhow!("Failed to read Pisi index file {}: {}", path.display(), e))?;
+    open_index_reader(file, IndexCompression::from_extension(path))
+}
+
+/// Arayüzden gelen bir indeks yolunu doğrular. Sembolik bağlar çözülür; sonuç
+/// `INDEX_FILE_EXTENSIONS` uzantılı normal bir dosya olmalıdır. Böylece önizleme
+/// komutu `/proc`, aygıt dosyaları veya rastgele yapılandırma dosyalarını okumaz.
+pub fn checked_index_path(path: &Path) -> Result<PathBuf, PmError> {
+    let invalid = |reason: &str| PmError::InvalidRequest { message: format!("{}: {}", path.display(), reason) };
+    if !path.is_absolute() {
+        return Err(invalid("path must be absolute"));
+    }
+    let resolved = fs::canonicalize(path).map_err(|e| invalid(&e.to_string()))?;
+    let extension = resolved.extension().and_then(|e| e.to_str()).unwrap_or_default();
+    if !INDEX_FILE_EXTENSIONS.contains(&extension) {
+        return Err(invalid(&format!("not an index file (expected .{})", INDEX_FILE_EXTENSIONS.join(", ."))));
+    }
+    if !fs::metadata(&resolved).map_err(|e| invalid(&e.to_string()))?.is_file() {
+        return Err(invalid("not a regular file"));
+    }
+    Ok(resolved)
+}
@@ -0,0 +1,97 @@
+use serde::{Serialize, Deserialize};
+use std::cmp::Ordering;
+use std::fmt;
+use crate::backend::version::compare_versions;
+
+/// PiSi'nin `<Dependency>`, `<Package>` (Conflicts/Replaces) elemanlarındaki
+/// karşılaştırma öznitelikleri. Tüm alanlar birlikte (VE ile) uygulanır:
+///
+/// - `version` / `release`: tam eşleşme
+/// - `versionFrom` / `releaseFrom`: alt sınır (dahil)
+/// - `versionTo` / `releaseTo`: üst sınır (dahil)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DependencyConstraint {
+    pub version: Option<String>,
+    pub version_from: Option<String>,
+    pub version_to: Option<String>,
+    pub release: Option<u32>,
+    pub release_from: Option<u32>,
+    pub release_to: Option<u32>,
+}
+
+impl DependencyConstraint {
+    /// XML düğümünün özniteliklerinden kısıtı okur
+    pub fn from_node(node: &roxmltree::Node) -> Self {
+        let text = |attr: &str| node.attribute(attr).map(|s| s.trim().to_string()).filter(|s| !s.is_empty());
+        let number = |attr: &str| node.attribute(attr).and_then(|s| s.trim().parse().ok());
+
+        Self {
+            version: text("version"),
+            version_from: text("versionFrom"),
+            version_to: text("versionTo"),
+            release: number("release"),
+            release_from: number("releaseFrom"),
+            release_to: number("releaseTo"),
+        }
+    }
+
+    /// Hiçbir kısıt yoksa (her sürüm kabul edilir) `true` döner
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Aday sürüm/yayın bu kısıtı sağlıyor mu? (pisi `Relation.satisfies_relation` ile aynı kurallar)
+    pub fn is_satisfied_by(&self, version: &str, release: u32) -> bool {
+        if let Some(exact) = &self.version {
+            if compare_versions(version, exact) != Ordering::Equal {
+                return false;
+            }
+        }
+        if let Some(from) = &self.version_from {
+            if compare_versions(version, from) == Ordering::Less {
+                return false;
+            }
+        }
+        if let Some(to) = &self.version_to {
+            if compare_versions(version, to) == Ordering::Greater {
+                return false;
+            }
+        }
+        if self.release.is_some_and(|r| release != r) {
+            return false;
+        }
+        if self.release_from.is_some_and(|r| release < r) {
+            return false;
+        }
+        if self.release_to.is_some_and(|r| release > r) {
+            return false;
+        }
+        true
+    }
+}
+
+impl fmt::Display for DependencyConstraint {
+    /// Örn: `>= 2.30, <= 2.40` veya `= 1.2 (release 3)`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut parts = Vec::new();
+        if let Some(v) = &self.version {
+            parts.push(format!("= {}", v));
+        }
+        if let Some(v) = &self.version_from {
+            parts.push(format!(">= {}", v));
+        }
+        if let Some(v) = &self.version_to {
+            parts.push(format!("<= {}", v));
+        }
+        if let Some(r) = self.release {
+            parts.push(format!("release = {}", r));
+        }
+        if let Some(r) = self.release_from {
+            parts.push(format!("release >= {}", r));
+        }
+        if let Some(r) = self.release_to {
+            parts.push(format!("release <= {}", r));
+        }
+        write!(f, "{}", parts.join(", "))
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Serialize, Deserialize};
+use std::fmt;
+use std::io;
+use crate::backend::names::NameError;
+use crate::backend::runner::PisiOutput;
+
+/// Arayüze dönen hata. `kind` alanına göre yerelleştirilmiş mesaj gösterilir,
+/// `message` ise pisi'nin özgün çıktısını (ayrıntı olarak) taşır.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind")]
+pub enum PmError {
+    /// pisi çalıştırılabilir dosyası (veya pkexec) bulunamadı
+    PisiNotFound,
+    /// Başka bir pisi süreci veritabanı kilidini tutuyor
+    LockHeld,
+    /// Yetki reddedildi ya da kimlik doğrulama iptal edildi
+    PermissionDenied { message: String },
+    PackageNotFound { message: String },
+    DependencyConflict { message: String },
+    NetworkUnavailable { message: String },
+    /// Depo indeksi yok; `update-repo` gerekli
+    IndexMissing { message: String },
+    ChecksumMismatch { message: String },
+    Cancelled,
+    /// Geçersiz istek (boş/yinelenen işlem listesi, geçersiz ad)
+    InvalidRequest { message: String },
+    /// Sınıflandırılamayan pisi hatası
+    Pisi { exit_code: i32, message: String },
+    Internal { message: String },
+}
+
+impl PmError {
+    /// Yeniden denemenin anlamlı olduğu (geçici) hatalar
+    pub fn is_retryable(&self) -> bool {
+        matches!(self, Self::LockHeld | Self::NetworkUnavailable { .. } | Self::ChecksumMismatch { .. })
+    }
+
+    /// Başarısız bir pisi çağrısını stderr içeriğine (LC_ALL=C) ve çıkış koduna göre sınıflandırır
+    pub fn from_pisi_output(output: &PisiOutput) -> Self {
+        let message = output.stderr.trim().to_string();
+        let lower = message.to_lowercase();
+        let has = |patterns: &[&str]| patterns.iter().any(|p| lower.contains(p));
+
+        // pkexec: 126 yetki verilmedi / iptal edildi, 127 kimlik doğrulama başarısız
+        if (output.exit_code == 126 || output.exit_code == 127)
+            && has(&["not authorized", "dismissed", "authentication", "authorization"])
+        {
+            return Self::PermissionDenied { message };
+        }
+        if has(&["another instance of pisi", "database is locked", "lock is held", "could not lock"]) {
+            return Self::LockHeld;
+        }
+        if has(&["have to be root", "requires root", "permission denied", "not permitted"]) {
+            return Self::PermissionDenied { message };
+        }
+        // Sadece indirilen dosyanın bozuk gelmesi yeniden denenebilir; bozuk yerel
+        // veritabanı tekrar denemekle düzelmez ve sınıflandırılmadan döner
+        let corrupt_download = has(&["corrupt"]) && has(&[".pisi", "archive"]) && !has(&["database"]);
+        if corrupt_download || has(&["hash mismatch", "hash doesn't match", "checksum mismatch", "wrong checksum", "sha1sum"]) {
+            return Self::ChecksumMismatch { message };
+        }
+        // HTTP yanıtı geldiyse sadece sunucu hataları (5xx) geçicidir; 404 gibi durumlar değil
+        let http_status = http_status(&lower);
+        let network_failure = has(&[
+            "could not fetch", "cannot fetch", "urlopen error", "connection refused", "connection reset",
+            "name or service not known", "temporary failure in name resolution", "unreachable", "timed out",
+        ]);
+        if http_status.map_or(network_failure, |status| (500..600).contains(&status)) {
+            return Self::NetworkUnavailable { message };
+        }
+        if has(&["not found in any active repository", "no such package", "not found in"]) {
+            return Self::PackageNotFound { message };
+        }
+        if has(&["conflict", "dependenc", "is required by", "cannot be satisfied"]) {
+            return Self::DependencyConflict { message };
+        }
+        if has(&["no repositories", "no repository", "repository index", "update-repo"]) {
+            return Self::IndexMissing { message };
+        }
+
+        Self::Pisi { exit_code: output.exit_code, message }
+    }
+}
+
+/// `HTTP Error 404: Not Found` gibi bir ifadedeki durum kodu
+fn http_status(lower: &str) -> Option<u16> {
+    let (_, rest) = lower.split_once("http error ")?;
+    rest.get(..3)?.parse().ok()
+}
+
+impl fmt::Display for PmError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PmError::PisiNotFound => write!(f, "pisi could not be found"),
+            PmError::LockHeld => write!(f, "Another pisi process is running"),
+            PmError::Cancelled => write!(f, "Operation cancelled by user"),
+            PmError::PermissionDenied { message }
+            | PmError::PackageNotFound { message }
+            | PmError::DependencyConflict { message }
+            | PmError::NetworkUnavailable { message }
+            | PmError::IndexMissing { message }
+            | PmError::ChecksumMismatch { message }
+            | PmError::InvalidRequest { message }
+            | PmError::Pisi { message, .. }
+            | PmError::Internal { message } => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for PmError {}
+
+impl From<io::Error> for PmError {
+    fn from(e: io::Error) -> Self {
+        match e.kind() {
+            io::ErrorKind::NotFound => PmError::PisiNotFound,
+            io::ErrorKind::PermissionDenied => PmError::PermissionDenied { message: e.to_string() },
+            _ => PmError::Internal { message: e.to_string() },
+        }
+    }
+}
+
+impl From<anyhow::Error> for PmError {
+    fn from(e: anyhow::Error) -> Self {
+        // Alt katmanların `PmError` olarak ürettiği hatalar olduğu gibi korunur
+        match e.downcast::<PmError>() {
+            Ok(error) => error,
+            Err(e) => PmError::Internal { message: e.to_string() },
+        }
+    }
+}
+
+impl From<NameError> for PmError {
+    fn from(e: NameError) -> Self {
+        PmError::InvalidRequest { message: e.to_string() }
+    }
+}
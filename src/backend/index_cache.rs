@@ -0,0 +1,206 @@
+use anyhow::Result;
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, Read, Write};
+use std::os::unix::fs::{DirBuilderExt, MetadataExt, OpenOptionsExt};
+use std::path::{Path, PathBuf};
+use xxhash_rust::xxh3::Xxh3;
+use crate::backend::xml_parser::PackageInfo;
+use crate::config::settings::AppSettings;
+
+/// Önbellek dosyasının ilk baytları
+pub const CACHE_MAGIC: [u8; 8] = *b"PISIPMIC";
+/// `PackageInfo` (veya önbellek düzeni) her değiştiğinde artırılmalı
+pub const CACHE_SCHEMA_VERSION: u32 = 3;
+/// Sihirli sayı, şema sürümü ve depo başlığının uzunluğu (u32)
+const PREAMBLE_LEN: usize = CACHE_MAGIC.len() + 4 + 4;
+
+/// Bir indeks dosyasının içeriğini tanımlayan anahtar. Değişiklik zamanı
+/// kullanılmaz; saat kayması veya korunan zaman damgası önbelleği yanıltmaz.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SourceKey {
+    pub size: u64,
+    /// Dosya içeriğinin XXH3 (64 bit) özeti
+    pub hash: u64,
+}
+
+impl SourceKey {
+    pub fn of_file(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut hasher = Xxh3::new();
+        let mut buf = vec![0u8; 64 * 1024];
+        let mut size = 0u64;
+        loop {
+            let n = file.read(&mut buf)?;
+            if n == 0 {
+                break;
+            }
+            hasher.update(&buf[..n]);
+            size += n as u64;
+        }
+        Ok(Self { size, hash: hasher.digest() })
+    }
+}
+
+/// Önbellekteki bir deponun parse edilmiş paketleri
+#[derive(Debug, Clone)]
+pub struct CachedRepository {
+    pub name: String,
+    pub key: SourceKey,
+    pub packages: Vec<PackageInfo>,
+}
+
+/// Başlıktaki depo kaydı; `len` deponun paket listesinin bayt uzunluğudur
+#[derive(Debug, Serialize, Deserialize)]
+struct EntryHeader {
+    name: String,
+    key: SourceKey,
+    len: u64,
+}
+
+/// Parse edilmiş paket listelerinin depo başına disk önbelleği.
+///
+/// Dosya düzeni: `CACHE_MAGIC`, `CACHE_SCHEMA_VERSION` (u32, little endian), başlık
+/// uzunluğu (u32), bincode ile yazılmış depo başlığı (ad, `SourceKey`, uzunluk) ve
+/// ardından depoların bincode ile yazılmış `Vec<PackageInfo>` listeleri sırayla.
+/// Sihirli sayı veya sürüm tutmazsa dosya silinir; anahtarı tutmayan depolar ise
+/// sadece kendi başlarına yeniden parse edilir.
+pub struct IndexCache {
+    path: PathBuf,
+}
+
+impl IndexCache {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+
+    /// `$XDG_CACHE_HOME/pisi-package-manager/index-cache.bin`; kullanıcı dizini
+    /// bilinmiyorsa `None` (disk önbelleği kullanılmaz)
+    pub fn default_path() -> Option<PathBuf> {
+        Some(AppSettings::cache_dir()?.join("index-cache.bin"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Adı ve anahtarı `wanted` içindekilerle uyuşan depoların paketlerini döner.
+    /// Dosya yoksa boş döner; başka bir kullanıcıya aitse veya bozuksa silinir.
+    pub fn load(&self, wanted: &[(String, SourceKey)]) -> HashMap<String, Vec<PackageInfo>> {
+        let mut found = HashMap::new();
+        let Ok(metadata) = fs::symlink_metadata(&self.path) else {
+            return found;
+        };
+        let bytes = match Self::check_owner(&metadata).and_then(|()| fs::read(&self.path).map_err(Into::into)) {
+            Ok(bytes) => bytes,
+            Err(e) => return self.discard(&e.to_string()),
+        };
+
+        if bytes.len() < PREAMBLE_LEN {
+            return self.discard("file is shorter than the header");
+        }
+        if bytes[..CACHE_MAGIC.len()] != CACHE_MAGIC {
+            return self.discard("bad magic");
+        }
+        let version = u32::from_le_bytes(bytes[8..12].try_into().unwrap());
+        if version != CACHE_SCHEMA_VERSION {
+            return self.discard(&format!("schema version {} (expected {})", version, CACHE_SCHEMA_VERSION));
+        }
+        let header_len = u32::from_le_bytes(bytes[12..16].try_into().unwrap()) as usize;
+        let Some(header) = bytes.get(PREAMBLE_LEN..PREAMBLE_LEN + header_len) else {
+            return self.discard("truncated header");
+        };
+        let entries: Vec<EntryHeader> = match bincode::deserialize(header) {
+            Ok(entries) => entries,
+            Err(e) => return self.discard(&format!("could not deserialize header: {}", e)),
+        };
+
+        let mut offset = PREAMBLE_LEN + header_len;
+        for entry in entries {
+            let start = offset;
+            offset = offset.saturating_add(entry.len as usize);
+            let Some(payload) = bytes.get(start..offset) else {
+                return self.discard("truncated payload");
+            };
+            if !wanted.iter().any(|(name, key)| *name == entry.name && *key == entry.key) {
+                println!("Binary cache of {} is stale", entry.name);
+                continue;
+            }
+            match bincode::deserialize::<Vec<PackageInfo>>(payload) {
+                Ok(packages) => {
+                    found.insert(entry.name, packages);
+                }
+                Err(e) => return self.discard(&format!("could not deserialize {}: {}", entry.name, e)),
+            }
+        }
+        found
+    }
+
+    /// Önbelleği aynı dizindeki geçici dosyaya yazıp yerine taşır; yarım kalmış
+    /// bir yazma hiçbir zaman okunmaz. Dizin ve dosya sadece sahibince okunabilir.
+    pub fn save(&self, repos: &[CachedRepository]) -> Result<()> {
+        let dir = self.path.parent().ok_or_else(|| anyhow::anyhow!("Invalid cache path {}", self.path.display()))?;
+        fs::DirBuilder::new().recursive(true).mode(0o700).create(dir)?;
+
+        let serialize_error = |e: bincode::Error| anyhow::anyhow!("Failed to serialize cache: {}", e);
+        let mut entries = Vec::new();
+        let mut payload = Vec::new();
+        for repo in repos {
+            let start = payload.len();
+            bincode::serialize_into(&mut payload, &repo.packages).map_err(serialize_error)?;
+            entries.push(EntryHeader { name: repo.name.clone(), key: repo.key, len: (payload.len() - start) as u64 });
+        }
+        let header = bincode::serialize(&entries).map_err(serialize_error)?;
+
+        let mut bytes = Vec::with_capacity(PREAMBLE_LEN + header.len() + payload.len());
+        bytes.extend_from_slice(&CACHE_MAGIC);
+        bytes.extend_from_slice(&CACHE_SCHEMA_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&(header.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&header);
+        bytes.extend_from_slice(&payload);
+
+        let file_name = self.path.file_name().and_then(|n| n.to_str()).unwrap_or("index-cache.bin");
+        let temp = dir.join(format!(".{}.{}.tmp", file_name, std::process::id()));
+        let written = OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .mode(0o600)
+            .open(&temp)
+            .and_then(|mut file| {
+                file.write_all(&bytes)?;
+                file.sync_all()
+            })
+            .and_then(|()| fs::rename(&temp, &self.path));
+        if let Err(e) = written {
+            let _ = fs::remove_file(&temp);
+            return Err(anyhow::anyhow!("Failed to write cache file {}: {}", self.path.display(), e));
+        }
+
+        println!("Saved {} repositories to binary cache: {}", repos.len(), self.path.display());
+        Ok(())
+    }
+
+    /// Sadece bu sürecin kullanıcısına ait, başkalarınca yazılamayan normal dosyalar okunur
+    fn check_owner(metadata: &fs::Metadata) -> Result<()> {
+        if !metadata.file_type().is_file() {
+            return Err(anyhow::anyhow!("not a regular file"));
+        }
+        let uid = unsafe { libc::geteuid() };
+        if metadata.uid() != uid {
+            return Err(anyhow::anyhow!("owned by uid {} instead of {}", metadata.uid(), uid));
+        }
+        if metadata.mode() & 0o022 != 0 {
+            return Err(anyhow::anyhow!("writable by other users"));
+        }
+        Ok(())
+    }
+
+    fn discard(&self, reason: &str) -> HashMap<String, Vec<PackageInfo>> {
+        println!("Discarding binary cache {}: {}", self.path.display(), reason);
+        if let Err(e) = fs::remove_file(&self.path) {
+            println!("Failed to remove {}: {}", self.path.display(), e);
+        }
+        HashMap::new()
+    }
+}
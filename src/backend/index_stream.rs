@@ -0,0 +1,123 @@
+use anyhow::Result;
+use quick_xml::events::Event;
+use quick_xml::{Reader, Writer};
+use roxmltree::Document;
+use std::io::BufRead;
+use crate::backend::xml_parser::{PackageInfo, XmlParser};
+
+/// pisi-index.xml dosyasını `<Package>` elemanı başına okuyan akış (streaming) parser.
+///
+/// Tüm indeks için tek bir DOM kurmak yerine, kök düğüm altındaki her `<Package>`
+/// elemanı küçük bir tampona kopyalanır ve sadece o eleman parse edilir.
+/// Böylece bellek kullanımı indeksin boyutuyla değil, en büyük paket kaydıyla sınırlı kalır.
+pub struct PackageStream<R: BufRead> {
+    reader: Reader<R>,
+    buf: Vec<u8>,
+    depth: usize,
+    finished: bool,
+}
+
+impl<R: BufRead> PackageStream<R> {
+    pub fn new(reader: R) -> Self {
+        Self {
+            reader: Reader::from_reader(reader),
+            buf: Vec::new(),
+            depth: 0,
+            finished: false,
+        }
+    }
+
+    /// Açılış etiketi okunmuş bir `<Package>` elemanını kapanışına kadar ayrı bir XML parçasına yazar
+    fn capture_package(&mut self, start: quick_xml::events::BytesStart<'static>) -> Result<Vec<u8>> {
+        let mut writer = Writer::new(Vec::new());
+        writer.write_event(Event::Start(start))?;
+
+        let mut nested = 1usize;
+        let mut buf = Vec::new();
+        loop {
+            let event = self.reader.read_event_into(&mut buf)?;
+            match &event {
+                Event::Start(_) => nested += 1,
+                Event::End(_) => nested -= 1,
+                Event::Eof => return Err(anyhow::anyhow!("Unexpected end of Pisi index inside <Package>")),
+                _ => {}
+            }
+            writer.write_event(event)?;
+            buf.clear();
+
+            if nested == 0 {
+                return Ok(writer.into_inner());
+            }
+        }
+    }
+
+    fn next_package(&mut self) -> Result<Option<PackageInfo>> {
+        loop {
+            self.buf.clear();
+            let event = self.reader.read_event_into(&mut self.buf)?;
+            match event {
+                // Sadece kök düğüm altındaki <Package> tag'leri (DOM parser ile aynı davranış)
+                Event::Start(e) if self.depth == 1 && e.local_name().as_ref() == b"Package" => {
+                    let start = e.into_owned();
+                    let fragment = self.capture_package(start)?;
+                    let fragment = std::str::from_utf8(&fragment)?;
+
+                    let doc = Document::parse(fragment)?;
+                    if let Some(package) = XmlParser::parse_package_node(&doc.root_element()) {
+                        return Ok(Some(package));
+                    }
+                }
+                Event::Start(_) => self.depth += 1,
+                Event::End(_) => self.depth = self.depth.saturating_sub(1),
+                Event::Eof => return Ok(None),
+                _ => {}
+            }
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for PackageStream<R> {
+    type Item = Result<PackageInfo>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+
+        match self.next_package() {
+            Ok(Some(package)) => Some(Ok(package)),
+            Ok(None) => {
+                self.finished = true;
+                None
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Kök düğüm altındaki `<Package>` elemanlarını parse etmeden sayar
+/// (depo listesinde paket sayısını göstermek için)
+pub fn count_packages<R: BufRead>(reader: R) -> Result<usize> {
+    let mut reader = Reader::from_reader(reader);
+    let mut buf = Vec::new();
+    let mut depth = 0usize;
+    let mut count = 0;
+
+    loop {
+        match reader.read_event_into(&mut buf)? {
+            Event::Start(e) => {
+                if depth == 1 && e.local_name().as_ref() == b"Package" {
+                    count += 1;
+                }
+                depth += 1;
+            }
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Eof => return Ok(count),
+            _ => {}
+        }
+        buf.clear();
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Serialize, Deserialize};
+use sha1::{Digest, Sha1};
+use std::fs::{self, File};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use crate::backend::error::PmError;
+use crate::backend::xml_parser::RepositoryIndex;
+use crate::config::settings::AppSettings;
+
+/// PiSi depolarının indeksin yanında yayınladığı SHA1 dosyasının uzantısı
+pub const CHECKSUM_SUFFIX: &str = ".sha1sum";
+/// Ayrık (detached) GPG imzası uzantısı
+pub const SIGNATURE_SUFFIX: &str = ".sig";
+const GPGV: &str = "gpgv";
+
+/// Yerel indeksin bütünlük durumu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum TrustStatus {
+    /// SHA1 tutuyor ve imza anahtarlıktaki bir anahtarla doğrulandı
+    Signed,
+    /// SHA1 tutuyor; anahtarlık ayarlanmamış
+    ChecksumOnly,
+    /// Anahtarlık ayarlanmamış ve indeksin yanında SHA1 dosyası yok
+    Unverified,
+    /// Anahtarlık ayarlı ama indeksin yanında imza yok
+    MissingSignature,
+    ChecksumMismatch { expected: String, found: String },
+    /// İmza doğrulanamadı (geçersiz imza, bilinmeyen anahtar veya `gpgv` yok)
+    BadSignature { message: String },
+}
+
+impl TrustStatus {
+    /// Bu durumdaki bir indeksin paketleri kullanılmaz ve önbelleğe alınmaz
+    pub fn is_rejected(&self) -> bool {
+        matches!(self, Self::ChecksumMismatch { .. } | Self::MissingSignature | Self::BadSignature { .. })
+    }
+}
+
+/// İndeks dosyalarını SHA1 ve (anahtarlık ayarlıysa) GPG imzasıyla doğrular
+#[derive(Debug, Clone, Default)]
+pub struct IndexVerifier {
+    keyring: Option<PathBuf>,
+}
+
+impl IndexVerifier {
+    pub fn new(keyring: Option<PathBuf>) -> Self {
+        Self { keyring }
+    }
+
+    pub fn from_settings(settings: &AppSettings) -> Self {
+        Self::new(settings.index_keyring.as_ref().filter(|k| !k.is_empty()).map(PathBuf::from))
+    }
+
+    /// `index` dosyasını yanındaki `.sha1sum` ve `.sig` dosyalarıyla karşılaştırır.
+    /// Anahtarlık ayarlıysa imza zorunludur; SHA1 dosyası olmasa bile imzası
+    /// olmayan indeks kabul edilmez.
+    pub fn verify(&self, index: &Path) -> TrustStatus {
+        let checksum_path = sidecar(index, CHECKSUM_SUFFIX);
+        let has_checksum = match fs::read_to_string(&checksum_path) {
+            Ok(content) => {
+                // `sha1sum` biçimi: `<hash>  <dosya adı>`; sadece hash de kabul edilir
+                let expected = content.split_whitespace().next().unwrap_or_default().to_lowercase();
+                let found = match sha1_file(index) {
+                    Ok(found) => found,
+                    Err(e) => {
+                        println!("Failed to hash {}: {}", index.display(), e);
+                        String::new()
+                    }
+                };
+                if found != expected {
+                    return TrustStatus::ChecksumMismatch { expected, found };
+                }
+                true
+            }
+            Err(_) => false,
+        };
+
+        let Some(keyring) = &self.keyring else {
+            return if has_checksum { TrustStatus::ChecksumOnly } else { TrustStatus::Unverified };
+        };
+        let signature = sidecar(index, SIGNATURE_SUFFIX);
+        if !signature.is_file() {
+            return TrustStatus::MissingSignature;
+        }
+        match verify_signature(index, &signature, keyring) {
+            Ok(()) => TrustStatus::Signed,
+            Err(message) => TrustStatus::BadSignature { message },
+        }
+    }
+
+    /// Deponun indeksini doğrular; reddedilirse nedeniyle birlikte `ChecksumMismatch` döner
+    pub fn check_repository(&self, repo: &RepositoryIndex) -> Result<(), PmError> {
+        let reason = match self.verify(&repo.path) {
+            TrustStatus::ChecksumMismatch { expected, found } => format!("SHA1 {} does not match {}", found, expected),
+            TrustStatus::MissingSignature => format!("no {} signature next to the index", SIGNATURE_SUFFIX),
+            TrustStatus::BadSignature { message } => format!("bad signature: {}", message),
+            _ => return Ok(()),
+        };
+        Err(PmError::ChecksumMismatch {
+            message: format!("Index of repository {} ({}) failed verification: {}", repo.name, repo.path.display(), reason),
+        })
+    }
+}
+
+pub fn sha1_file(path: &Path) -> io::Result<String> {
+    let mut hasher = Sha1::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+/// `gpgv` ile ayrık imzayı sadece verilen anahtarlığa göre doğrular
+fn verify_signature(index: &Path, signature: &Path, keyring: &Path) -> Result<(), String> {
+    let output = Command::new(GPGV)
+        .env("LC_ALL", "C")
+        .arg("--keyring")
+        .arg(keyring)
+        .arg(signature)
+        .arg(index)
+        .output()
+        .map_err(|e| format!("could not run {}: {}", GPGV, e))?;
+
+    if output.status.success() {
+        Ok(())
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        Err(stderr.lines().last().unwrap_or("gpgv failed").trim().to_string())
+    }
+}
+
+fn sidecar(index: &Path, suffix: &str) -> PathBuf {
+    let mut path = index.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
+}
@@ -0,0 +1,204 @@
+use anyhow::Result;
+use roxmltree::Document;
+use serde::{Serialize, Deserialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+use crate::backend::xml_parser::{Dependency, XmlParser};
+
+/// Paketin neden kurulu olduğu.
+/// PiSi bunu kaydetmediği için kurulu paketler arasındaki bağımlılıklardan çıkarılır:
+/// başka bir kurulu paketin çalışma zamanı bağımlılığı olan paket `Dependency` sayılır.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum InstallReason {
+    Explicit,
+    Dependency,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledPackage {
+    pub name: String,
+    pub version: String,
+    pub release: u32,
+    pub summary: String,
+    pub part_of: String,
+    pub distribution: String,
+    pub distribution_release: String,
+    pub architecture: String,
+    pub installed_size: u64,
+    /// Kurulum zamanı (UNIX saniyesi, `files.xml` dosyasının zamanı)
+    pub install_date: Option<u64>,
+    pub reason: InstallReason,
+    pub dependencies: Vec<Dependency>,
+    /// Bu paketle birlikte kurulamayacak paketler (`metadata.xml` `<Conflicts>`)
+    #[serde(default)]
+    pub conflicts: Vec<Dependency>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledFile {
+    pub path: String,
+    pub file_type: String,
+    pub size: u64,
+    pub hash: Option<String>,
+}
+
+/// `<root>/var/lib/pisi/package/<name>/` altındaki kurulu paket veritabanı okuyucusu
+pub struct InstalledDb {
+    root: PathBuf,
+}
+
+impl InstalledDb {
+    const PACKAGE_DIR: &'static str = "var/lib/pisi/package";
+
+    /// Test ve chroot kullanımı için farklı bir kök dizin ile açar
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Çalışan sistemin veritabanı
+    pub fn system() -> Self {
+        Self::new("/")
+    }
+
+    fn package_dir(&self) -> PathBuf {
+        self.root.join(Self::PACKAGE_DIR)
+    }
+
+    /// Tüm kurulu paketleri isim sırasıyla döner
+    pub fn list(&self) -> Result<Vec<InstalledPackage>> {
+        let dir = self.package_dir();
+        if !dir.is_dir() {
+            return Err(anyhow::anyhow!("Installed package database not found: {}", dir.display()));
+        }
+
+        let mut packages = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            if entry.path().join("metadata.xml").is_file() {
+                packages.push(Self::read_package(&entry.path())?);
+            }
+        }
+        packages.sort_by(|a, b| a.name.cmp(&b.name));
+
+        // Başka bir kurulu paketin ihtiyaç duyduğu paketler bağımlılık olarak kurulmuştur
+        let required: HashSet<String> = packages
+            .iter()
+            .flat_map(|p| p.dependencies.iter().map(|d| d.name.clone()))
+            .collect();
+        for package in &mut packages {
+            if required.contains(&package.name) {
+                package.reason = InstallReason::Dependency;
+            }
+        }
+
+        Ok(packages)
+    }
+
+    /// Sadece kurulu paket isimleri (metadata parse etmeden, dizin adlarından)
+    pub fn names(&self) -> Result<Vec<String>> {
+        let dir = self.package_dir();
+        if !dir.is_dir() {
+            return Err(anyhow::anyhow!("Installed package database not found: {}", dir.display()));
+        }
+
+        let mut names = Vec::new();
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            if entry.path().join("metadata.xml").is_file() {
+                names.push(entry.file_name().to_string_lossy().to_string());
+            }
+        }
+        names.sort();
+        Ok(names)
+    }
+
+    pub fn get(&self, name: &str) -> Result<Option<InstalledPackage>> {
+        Ok(self.list()?.into_iter().find(|p| p.name == name))
+    }
+
+    pub fn is_installed(&self, name: &str) -> bool {
+        self.package_dir().join(name).join("metadata.xml").is_file()
+    }
+
+    /// Paketin `files.xml` dosyasındaki dosya listesi
+    pub fn files(&self, name: &str) -> Result<Vec<InstalledFile>> {
+        let path = self.package_dir().join(name).join("files.xml");
+        let content = fs::read_to_string(&path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", path.display(), e))?;
+        Self::parse_files(&content)
+    }
+
+    fn read_package(dir: &Path) -> Result<InstalledPackage> {
+        let metadata_path = dir.join("metadata.xml");
+        let content = fs::read_to_string(&metadata_path)
+            .map_err(|e| anyhow::anyhow!("Failed to read {}: {}", metadata_path.display(), e))?;
+        let doc = Document::parse(&content)
+            .map_err(|e| anyhow::anyhow!("Failed to parse {}: {}", metadata_path.display(), e))?;
+
+        let package_node = doc.root_element()
+            .children()
+            .find(|n| n.has_tag_name("Package"))
+            .ok_or_else(|| anyhow::anyhow!("No <Package> in {}", metadata_path.display()))?;
+        let info = XmlParser::parse_package_node(&package_node)
+            .ok_or_else(|| anyhow::anyhow!("Invalid package metadata in {}", metadata_path.display()))?;
+
+        let files_path = dir.join("files.xml");
+        let install_date = fs::metadata(&files_path)
+            .or_else(|_| fs::metadata(&metadata_path))
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+
+        // metadata.xml'de boyut yoksa files.xml'deki dosya boyutları toplanır
+        let installed_size = if info.installed_size > 0 {
+            info.installed_size
+        } else {
+            fs::read_to_string(&files_path)
+                .ok()
+                .and_then(|c| Self::parse_files(&c).ok())
+                .map(|files| files.iter().map(|f| f.size).sum())
+                .unwrap_or(0)
+        };
+
+        Ok(InstalledPackage {
+            name: info.name,
+            version: info.version,
+            release: info.release,
+            summary: info.summary,
+            part_of: info.part_of,
+            distribution: info.distribution,
+            distribution_release: info.distribution_release,
+            architecture: info.architecture,
+            installed_size,
+            install_date,
+            reason: InstallReason::Explicit,
+            dependencies: info.dependencies,
+            conflicts: info.conflicts,
+        })
+    }
+
+    fn parse_files(xml_content: &str) -> Result<Vec<InstalledFile>> {
+        let doc = Document::parse(xml_content)?;
+        let child_text = |node: &roxmltree::Node, tag: &str| node.children()
+            .find(|n| n.has_tag_name(tag))
+            .and_then(|n| n.text())
+            .map(|s| s.trim().to_string());
+
+        Ok(doc.root_element()
+            .children()
+            .filter(|n| n.has_tag_name("File"))
+            .filter_map(|node| {
+                let path = child_text(&node, "Path")?;
+                Some(InstalledFile {
+                    path,
+                    file_type: child_text(&node, "Type").unwrap_or_default(),
+                    size: child_text(&node, "Size").and_then(|s| s.parse().ok()).unwrap_or(0),
+                    hash: child_text(&node, "Hash"),
+                })
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,237 @@
+use serde::{Serialize, Deserialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use crate::backend::error::PmError;
+use crate::backend::names::RepoName;
+use crate::backend::repo_probe::RepositoryUrl;
+use crate::config::settings::AppSettings;
+
+/// Hız testinde her yansıdan indirilecek en fazla veri
+pub const MIRROR_SAMPLE_BYTES: usize = 512 * 1024;
+/// Kaydedilmiş test sonuçlarının geçerli sayıldığı süre
+pub const MIRROR_CACHE_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+const MIRROR_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Yansı listesindeki bir kayıt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Mirror {
+    /// Yansının kopyaladığı depo (`pisi list-repo` adı); depo sadece kendi yansılarına geçirilebilir
+    pub repository: String,
+    /// İndeks dosyasının adresi (depo adresiyle aynı biçimde)
+    pub url: String,
+    /// Adresten sonra yazılan serbest açıklama (ör. `Avrupa/Almanya`)
+    pub location: Option<String>,
+}
+
+/// Yansı listesi dosyasını okur. Her satırda depo adı, indeks adresi ve isteğe bağlı
+/// konum bilgisi bulunur; boş satırlar ve `#` ile başlayan satırlar atlanır:
+///
+/// ```text
+/// # depo    adres                                              konum
+/// pisi-2.0  https://mirror.example.org/pisi/2.0/pisi-index.xml.xz  Avrupa/Almanya
+/// ```
+pub fn parse_mirror_list(content: &str) -> Result<Vec<Mirror>, PmError> {
+    let mut mirrors = Vec::new();
+
+    for line in content.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let Some((repository, rest)) = line.split_once(char::is_whitespace) else {
+            return Err(PmError::InvalidRequest { message: format!("Mirror line \"{}\" has no repository column", line) });
+        };
+        let repository = RepoName::new(repository)?;
+        let (url, location) = match rest.trim_start().split_once(char::is_whitespace) {
+            Some((url, location)) => (url, Some(location.trim().to_string())),
+            None => (rest.trim_start(), None),
+        };
+        if !matches!(RepositoryUrl::parse(url)?, RepositoryUrl::Http(_)) {
+            return Err(PmError::InvalidRequest { message: format!("Mirror {} is not an http(s) URL", url) });
+        }
+        mirrors.push(Mirror { repository: repository.as_str().to_string(), url: url.to_string(), location });
+    }
+
+    Ok(mirrors)
+}
+
+pub fn load_mirror_list(path: &Path) -> Result<Vec<Mirror>, PmError> {
+    let content = fs::read_to_string(path).map_err(|e| PmError::InvalidRequest {
+        message: format!("Could not read mirror list {}: {}", path.display(), e),
+    })?;
+    parse_mirror_list(&content)
+}
+
+/// Bir yansının ölçüm sonucu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MirrorResult {
+    pub mirror: Mirror,
+    /// İsteğin gönderilmesinden yanıt başlıklarının gelmesine kadar geçen süre
+    pub latency_ms: Option<u64>,
+    /// İndirme hızı (bayt/saniye)
+    pub throughput_bps: Option<u64>,
+    /// Yansıya ulaşılamadıysa nedeni
+    pub error: Option<PmError>,
+}
+
+impl MirrorResult {
+    pub fn is_reachable(&self) -> bool {
+        self.error.is_none()
+    }
+}
+
+/// Zaman damgalı hız testi sonucu; sonuçlar en hızlıdan yavaşa sıralıdır
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MirrorReport {
+    /// Testin yapıldığı zaman (UNIX saniyesi)
+    pub tested_at: u64,
+    pub results: Vec<MirrorResult>,
+}
+
+impl MirrorReport {
+    pub fn fastest(&self) -> Option<&Mirror> {
+        self.results.iter().find(|r| r.is_reachable()).map(|r| &r.mirror)
+    }
+
+    /// Verilen deponun ulaşılabilen en hızlı yansısı
+    pub fn fastest_for(&self, repository: &str) -> Option<&Mirror> {
+        self.results
+            .iter()
+            .find(|r| r.is_reachable() && r.mirror.repository == repository)
+            .map(|r| &r.mirror)
+    }
+
+    /// Rapor `max_age` süresinden eski değilse ve aynı yansı listesi için yapıldıysa geçerlidir
+    pub fn is_fresh(&self, mirrors: &[Mirror], max_age: Duration) -> bool {
+        let tested: Vec<&Mirror> = self.results.iter().map(|r| &r.mirror).collect();
+        let same_list = tested.len() == mirrors.len() && mirrors.iter().all(|m| tested.contains(&m));
+        same_list && now_secs().saturating_sub(self.tested_at) <= max_age.as_secs()
+    }
+
+    /// `$XDG_CACHE_HOME/pisi-package-manager/mirrors.json`; kullanıcı dizini bilinmiyorsa `None`
+    pub fn cache_path() -> Option<PathBuf> {
+        Some(AppSettings::cache_dir()?.join("mirrors.json"))
+    }
+
+    /// Kaydedilmiş raporu okur; dosya yoksa veya bozuksa `None`
+    pub fn load(path: &Path) -> Option<Self> {
+        let content = fs::read_to_string(path).ok()?;
+        match serde_json::from_str(&content) {
+            Ok(report) => Some(report),
+            Err(e) => {
+                println!("Ignoring invalid mirror cache {}: {}", path.display(), e);
+                None
+            }
+        }
+    }
+
+    pub fn save(&self, path: &Path) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+/// Yansı listesi için geçerli bir kayıtlı rapor varsa onu, yoksa (veya `force` verilirse)
+/// yeni bir test yapıp kaydettiği raporu döner. `cache_path` yoksa her seferinde ölçülür.
+pub async fn mirror_report(list_path: &Path, cache_path: Option<&Path>, force: bool) -> Result<MirrorReport, PmError> {
+    let mirrors = load_mirror_list(list_path)?;
+    if let (false, Some(cache_path)) = (force, cache_path) {
+        if let Some(report) = MirrorReport::load(cache_path).filter(|r| r.is_fresh(&mirrors, MIRROR_CACHE_MAX_AGE)) {
+            return Ok(report);
+        }
+    }
+
+    let report = measure_mirrors(&mirrors).await?;
+    if let Some(cache_path) = cache_path {
+        if let Err(e) = report.save(cache_path) {
+            println!("Failed to save mirror cache {}: {}", cache_path.display(), e);
+        }
+    }
+    Ok(report)
+}
+
+/// Yansıları sırayla (birbirinin bant genişliğini etkilemesin diye) ölçer ve sıralar
+pub async fn measure_mirrors(mirrors: &[Mirror]) -> Result<MirrorReport, PmError> {
+    let client = reqwest::Client::builder()
+        .timeout(MIRROR_TIMEOUT)
+        .build()
+        .map_err(|e| PmError::Internal { message: e.to_string() })?;
+
+    let mut results = Vec::new();
+    for mirror in mirrors {
+        println!("Testing mirror {}", mirror.url);
+        results.push(measure(&client, mirror).await);
+    }
+    rank(&mut results);
+
+    Ok(MirrorReport { tested_at: now_secs(), results })
+}
+
+/// Ulaşılabilen yansılar önce gelir; aralarında hızlı olan, hız eşitse gecikmesi düşük olan önde
+pub fn rank(results: &mut [MirrorResult]) {
+    results.sort_by_key(|r| {
+        (
+            !r.is_reachable(),
+            std::cmp::Reverse(r.throughput_bps.unwrap_or(0)),
+            r.latency_ms.unwrap_or(u64::MAX),
+        )
+    });
+}
+
+/// Yansıdan en fazla `MIRROR_SAMPLE_BYTES` indirerek gecikme ve hızı ölçer
+async fn measure(client: &reqwest::Client, mirror: &Mirror) -> MirrorResult {
+    let failed = |error: PmError| MirrorResult {
+        mirror: mirror.clone(),
+        latency_ms: None,
+        throughput_bps: None,
+        error: Some(error),
+    };
+    let network_error = |e: reqwest::Error| PmError::NetworkUnavailable {
+        message: format!("Could not fetch {}: {}", mirror.url, e),
+    };
+
+    let started = Instant::now();
+    let response = client
+        .get(&mirror.url)
+        .header(reqwest::header::RANGE, format!("bytes=0-{}", MIRROR_SAMPLE_BYTES - 1))
+        .send()
+        .await;
+    let mut response = match response {
+        Ok(response) if response.status().is_success() => response,
+        Ok(response) => {
+            return failed(PmError::NetworkUnavailable {
+                message: format!("Could not fetch {}: HTTP {}", mirror.url, response.status()),
+            })
+        }
+        Err(e) => return failed(network_error(e)),
+    };
+    let latency = started.elapsed();
+
+    let download_started = Instant::now();
+    let mut received = 0usize;
+    while received < MIRROR_SAMPLE_BYTES {
+        match response.chunk().await {
+            Ok(Some(chunk)) => received += chunk.len(),
+            Ok(None) => break,
+            Err(e) => return failed(network_error(e)),
+        }
+    }
+    let elapsed = download_started.elapsed().as_secs_f64().max(0.001);
+
+    MirrorResult {
+        mirror: mirror.clone(),
+        latency_ms: Some(latency.as_millis() as u64),
+        throughput_bps: Some((received as f64 / elapsed) as u64),
+        error: None,
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
@@ -1,7 +1,43 @@
 pub mod package_manager;
 pub mod xml_parser;
+pub mod index_stream;
+pub mod compression;
+pub mod dependency;
+pub mod version;
+pub mod installed_db;
+pub mod resolver;
+pub mod reverse_deps;
+pub mod operation;
+pub mod transaction;
+pub mod privileged;
+pub mod names;
+pub mod runner;
+pub mod actions;
 pub mod repository;
+pub mod error;
+pub mod repo_probe;
+pub mod mirrors;
+pub mod index_verify;
+pub mod index_cache;
 
 pub use package_manager::*;
 pub use xml_parser::*;
+pub use index_stream::*;
+pub use compression::*;
+pub use dependency::*;
+pub use version::*;
+pub use installed_db::*;
+pub use resolver::*;
+pub use reverse_deps::*;
+pub use operation::*;
+pub use transaction::*;
+pub use privileged::*;
+pub use names::*;
+pub use runner::*;
+pub use actions::*;
 pub use repository::*;
+pub use error::*;
+pub use repo_probe::*;
+pub use mirrors::*;
+pub use index_verify::*;
+pub use index_cache::*;
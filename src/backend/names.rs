@@ -0,0 +1,143 @@
+use serde::{Serialize, Deserialize};
+use std::fmt;
+use std::str::FromStr;
+
+/// PiSi ad kurallarına uymayan paket/depo adı
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NameError {
+    Empty { kind: NameKind },
+    TooLong { kind: NameKind, length: usize },
+    /// Ad harf veya rakamla başlamalı (`-` ile başlayan ad pisi'ye seçenek olarak geçerdi)
+    InvalidStart { kind: NameKind, name: String },
+    InvalidCharacter { kind: NameKind, name: String, character: char },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum NameKind {
+    Package,
+    Repository,
+}
+
+impl fmt::Display for NameKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameKind::Package => write!(f, "package"),
+            NameKind::Repository => write!(f, "repository"),
+        }
+    }
+}
+
+impl fmt::Display for NameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NameError::Empty { kind } => write!(f, "Empty {} name", kind),
+            NameError::TooLong { kind, length } => {
+                write!(f, "Invalid {} name: {} characters (max {})", kind, length, MAX_NAME_LENGTH)
+            }
+            NameError::InvalidStart { kind, name } => {
+                write!(f, "Invalid {} name {:?}: must start with a letter or digit", kind, name)
+            }
+            NameError::InvalidCharacter { kind, name, character } => {
+                write!(f, "Invalid {} name {:?}: character {:?} is not allowed", kind, name, character)
+            }
+        }
+    }
+}
+
+impl std::error::Error for NameError {}
+
+/// Adlar `/var/lib/pisi/...` altında dizin adı olarak da kullanıldığı için dosya adı sınırı
+const MAX_NAME_LENGTH: usize = 255;
+
+fn validate(kind: NameKind, name: &str, extra_chars: &[char]) -> Result<(), NameError> {
+    let first = name.chars().next().ok_or(NameError::Empty { kind })?;
+    if name.len() > MAX_NAME_LENGTH {
+        return Err(NameError::TooLong { kind, length: name.len() });
+    }
+    if !first.is_ascii_alphanumeric() {
+        return Err(NameError::InvalidStart { kind, name: name.to_string() });
+    }
+    if let Some(character) = name
+        .chars()
+        .find(|c| !c.is_ascii_alphanumeric() && !extra_chars.contains(c))
+    {
+        return Err(NameError::InvalidCharacter { kind, name: name.to_string(), character });
+    }
+    Ok(())
+}
+
+/// Doğrulanmış paket adı: harf/rakamla başlar, sadece harf, rakam ve `-_.+` içerir.
+/// Tauri komutlarında parametre olarak kullanıldığında geçersiz ad daha komut çalışmadan reddedilir.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct PackageName(String);
+
+impl PackageName {
+    pub fn new(name: impl Into<String>) -> Result<Self, NameError> {
+        let name = name.into();
+        validate(NameKind::Package, &name, &['-', '_', '.', '+'])?;
+        Ok(Self(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Doğrulanmış depo adı: harf/rakamla başlar, sadece harf, rakam ve `-_.` içerir
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct RepoName(String);
+
+impl RepoName {
+    pub fn new(name: impl Into<String>) -> Result<Self, NameError> {
+        let name = name.into();
+        validate(NameKind::Repository, &name, &['-', '_', '.'])?;
+        Ok(Self(name))
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+macro_rules! impl_name_conversions {
+    ($name:ident) => {
+        impl TryFrom<String> for $name {
+            type Error = NameError;
+
+            fn try_from(value: String) -> Result<Self, Self::Error> {
+                Self::new(value)
+            }
+        }
+
+        impl From<$name> for String {
+            fn from(name: $name) -> Self {
+                name.0
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = NameError;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                Self::new(s)
+            }
+        }
+
+        impl AsRef<str> for $name {
+            fn as_ref(&self) -> &str {
+                &self.0
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str(&self.0)
+            }
+        }
+    };
+}
+
+impl_name_conversions!(PackageName);
+impl_name_conversions!(RepoName);
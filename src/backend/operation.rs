@@ -0,0 +1,439 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashMap;
+use std::fs::File;
+use std::os::unix::io::AsRawFd;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tokio::process::Command;
+use crate::backend::error::PmError;
+use crate::backend::privileged;
+use crate::backend::runner::PisiRunner;
+
+/// Arayüzün dinlediği Tauri olayı
+pub const PROGRESS_EVENT: &str = "operation-progress";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum OperationPhase {
+    Started,
+    Downloading,
+    Installing,
+    Extracting,
+    Configuring,
+    Removing,
+    Finished,
+    Failed,
+    Cancelled,
+}
+
+impl OperationPhase {
+    /// Bu aşamada kesilen bir işlem sistemde yarım kalmış değişiklik bırakabilir mi?
+    fn modifies_system(self) -> bool {
+        matches!(self, Self::Installing | Self::Extracting | Self::Configuring | Self::Removing)
+    }
+}
+
+/// Uzun süren pisi işlemlerinden gelen tek bir ilerleme bildirimi
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OperationProgress {
+    pub operation_id: String,
+    pub phase: OperationPhase,
+    pub package: Option<String>,
+    pub bytes_done: Option<u64>,
+    pub bytes_total: Option<u64>,
+    /// Mevcut paketin yüzdesi (indirme için)
+    pub percent: Option<u8>,
+    /// "Downloading 2 / 5" satırlarındaki sıra ve toplam
+    pub step: Option<usize>,
+    pub total_steps: Option<usize>,
+    /// pisi'nin yazdığı ham satır
+    pub message: String,
+}
+
+impl OperationProgress {
+    pub fn new(operation_id: &str, phase: OperationPhase, message: impl Into<String>) -> Self {
+        Self {
+            operation_id: operation_id.to_string(),
+            phase,
+            package: None,
+            bytes_done: None,
+            bytes_total: None,
+            percent: None,
+            step: None,
+            total_steps: None,
+            message: message.into(),
+        }
+    }
+}
+
+static NEXT_OPERATION_ID: AtomicU64 = AtomicU64::new(1);
+
+/// `install-3` gibi, uygulama çalıştığı sürece benzersiz bir işlem kimliği üretir
+pub fn next_operation_id(kind: &str) -> String {
+    format!("{}-{}", kind, NEXT_OPERATION_ID.fetch_add(1, Ordering::Relaxed))
+}
+
+/// pisi'nin (LC_ALL=C) çıktı satırlarını ilerleme bildirimine çevirir.
+/// Tanınmayan satırlar için `None` döner.
+pub fn parse_progress_line(operation_id: &str, line: &str) -> Option<OperationProgress> {
+    let line = line.trim();
+    if line.is_empty() {
+        return None;
+    }
+    let progress = |phase| OperationProgress::new(operation_id, phase, line);
+    let with_package = |phase, package: &str| OperationProgress {
+        package: Some(package.trim().to_string()),
+        ..progress(phase)
+    };
+
+    // "Downloading 1 / 3", "Installing 2 / 3"
+    for (prefix, phase) in [("Downloading ", OperationPhase::Downloading), ("Installing ", OperationPhase::Installing)] {
+        if let Some((step, total)) = line.strip_prefix(prefix).and_then(parse_step) {
+            return Some(OperationProgress { step: Some(step), total_steps: Some(total), ..progress(phase) });
+        }
+    }
+
+    if let Some(rest) = line.strip_prefix("Installing ") {
+        // "Installing curl, version 7.64.0, release 36"
+        let package = rest.split(',').next().unwrap_or(rest);
+        return Some(with_package(OperationPhase::Installing, package));
+    }
+    if let Some(package) = line.strip_prefix("Extracting the files of ") {
+        return Some(with_package(OperationPhase::Extracting, package));
+    }
+    if let Some(package) = line.strip_prefix("Configuring ") {
+        let package = package.strip_prefix("package ").unwrap_or(package);
+        return Some(with_package(OperationPhase::Configuring, package));
+    }
+    if let Some(package) = line.strip_prefix("Removing package ") {
+        return Some(with_package(OperationPhase::Removing, package));
+    }
+    if let Some(package) = line.strip_prefix("Installed ").or_else(|| line.strip_prefix("Upgraded ")) {
+        return Some(OperationProgress { percent: Some(100), ..with_package(OperationPhase::Installing, package) });
+    }
+    if let Some(package) = line.strip_prefix("Removed ") {
+        return Some(OperationProgress { percent: Some(100), ..with_package(OperationPhase::Removing, package) });
+    }
+
+    parse_fetch_line(line).map(|(file, total, percent)| OperationProgress {
+        package: Some(package_name_from_file(file)),
+        bytes_total: Some(total),
+        bytes_done: Some(total * percent as u64 / 100),
+        percent: Some(percent),
+        ..progress(OperationPhase::Downloading)
+    })
+}
+
+/// "2 / 5" -> (2, 5)
+fn parse_step(s: &str) -> Option<(usize, usize)> {
+    let (step, total) = s.split_once('/')?;
+    Some((step.trim().parse().ok()?, total.trim().parse().ok()?))
+}
+
+/// pisi indirme satırı: `curl-7.64.0-36-p1-x86_64.pisi (356.0 KB) 42%   318.81 KB/s [00:00:01]`
+fn parse_fetch_line(line: &str) -> Option<(&str, u64, u8)> {
+    let (file, rest) = line.split_once(" (")?;
+    let file = file.trim();
+    if !file.ends_with(".pisi") && !file.ends_with(".xml.xz") && !file.ends_with(".delta.pisi") {
+        return None;
+    }
+
+    let (size, rest) = rest.split_once(')')?;
+    let (amount, unit) = size.trim().split_once(' ')?;
+    let multiplier = match unit {
+        "B" => 1.0,
+        "KB" => 1024.0,
+        "MB" => 1024.0 * 1024.0,
+        "GB" => 1024.0 * 1024.0 * 1024.0,
+        _ => return None,
+    };
+    let total = (amount.parse::<f64>().ok()? * multiplier) as u64;
+
+    let percent = rest.trim_start().split('%').next()?.trim().parse::<u8>().ok()?;
+    Some((file, total, percent.min(100)))
+}
+
+/// `curl-7.64.0-36-p1-x86_64.pisi` -> `curl` (son dört `-` parçası sürüm/yayın/dağıtım/mimari)
+fn package_name_from_file(file: &str) -> String {
+    let stem = file.trim_end_matches(".pisi").trim_end_matches(".delta");
+    let parts: Vec<&str> = stem.rsplitn(5, '-').collect();
+    if parts.len() == 5 {
+        parts[4].to_string()
+    } else {
+        stem.to_string()
+    }
+}
+
+/// Çalışan bir işlemin kaydı
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RunningOperation {
+    pub operation_id: String,
+    pub command: String,
+    /// Çalışan pisi sürecinin pid'i; çok adımlı işlemlerde iki pisi çağrısı arasında 0
+    pub pid: u32,
+    /// Süreçten gelen son ilerleme aşaması
+    pub phase: OperationPhase,
+    pub cancel_requested: bool,
+}
+
+/// `cancel_operation` sonucu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelReport {
+    pub operation_id: String,
+    /// Süreç sonlandı mı?
+    pub terminated: bool,
+    /// pisi kilidi serbest bırakıldı mı?
+    pub lock_released: bool,
+    /// İptal, sistemi değiştirmeye başlamadan önce mi gerçekleşti?
+    pub consistent: bool,
+    /// İptal anındaki aşama
+    pub phase: OperationPhase,
+    pub message: String,
+}
+
+/// Çalışan pisi süreçlerini kimlikleriyle tutan kayıt (Tauri managed state)
+#[derive(Debug, Default)]
+pub struct OperationRegistry {
+    operations: Mutex<HashMap<String, RunningOperation>>,
+}
+
+impl OperationRegistry {
+    /// pisi'nin `/var/lock/subsys/pisi` üzerinde tuttuğu flock
+    const PISI_LOCK_PATH: &'static str = "/var/lock/subsys/pisi";
+    const TERMINATE_TIMEOUT: Duration = Duration::from_secs(15);
+    const LOCK_TIMEOUT: Duration = Duration::from_secs(30);
+    const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+    pub fn list(&self) -> Vec<RunningOperation> {
+        self.operations.lock().unwrap().values().cloned().collect()
+    }
+
+    /// İşlemi henüz bir pisi süreci olmadan kaydeder. Birden fazla pisi çağrısı yapan
+    /// işlemler (ör. `transaction::run_actions`) kimliklerini baştan sona kayıtlı tutar;
+    /// böylece iki çağrı arasında gelen iptal de kaybolmaz. İşlem zaten kayıtlıysa `false` döner.
+    pub fn begin(&self, operation_id: &str, command: &str) -> bool {
+        let mut operations = self.operations.lock().unwrap();
+        if operations.contains_key(operation_id) {
+            return false;
+        }
+        operations.insert(
+            operation_id.to_string(),
+            RunningOperation {
+                operation_id: operation_id.to_string(),
+                command: command.to_string(),
+                pid: 0,
+                phase: OperationPhase::Started,
+                cancel_requested: false,
+            },
+        );
+        true
+    }
+
+    /// Kaydı siler; işlem iptal istendiyse `true` döner
+    pub fn finish(&self, operation_id: &str) -> bool {
+        self.operations
+            .lock()
+            .unwrap()
+            .remove(operation_id)
+            .map(|op| op.cancel_requested)
+            .unwrap_or(false)
+    }
+
+    /// İşlemi iptal edildi olarak işaretler; sıradaki pisi çağrısı hiç başlatılmaz.
+    /// Kayıtlı işlem yoksa `false` döner.
+    pub fn request_cancel(&self, operation_id: &str) -> bool {
+        match self.operations.lock().unwrap().get_mut(operation_id) {
+            Some(op) => {
+                op.cancel_requested = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn is_cancel_requested(&self, operation_id: &str) -> bool {
+        self.operations.lock().unwrap().get(operation_id).is_some_and(|op| op.cancel_requested)
+    }
+
+    fn set_process(&self, operation_id: &str, command: &str, pid: u32) {
+        if let Some(op) = self.operations.lock().unwrap().get_mut(operation_id) {
+            op.command = command.to_string();
+            op.pid = pid;
+        }
+    }
+
+    fn set_phase(&self, operation_id: &str, phase: OperationPhase) {
+        if let Some(op) = self.operations.lock().unwrap().get_mut(operation_id) {
+            op.phase = phase;
+        }
+    }
+
+    fn pid(&self, operation_id: &str) -> Option<u32> {
+        self.operations.lock().unwrap().get(operation_id).map(|op| op.pid)
+    }
+
+    /// İşlemi durdurur: önce SIGINT (pisi kendi temizliğini yapar), zaman aşımında SIGKILL.
+    /// Ardından pisi kilidinin bırakılmasını bekler ve sistemin tutarlılığını raporlar.
+    pub async fn cancel(&self, operation_id: &str) -> Result<CancelReport, PmError> {
+        if !self.request_cancel(operation_id) {
+            return Err(PmError::InvalidRequest { message: format!("No running operation with id {}", operation_id) });
+        }
+        let phase = self
+            .operations
+            .lock()
+            .unwrap()
+            .get(operation_id)
+            .map_or(OperationPhase::Started, |op| op.phase);
+
+        let mut terminated = self.signal_until_finished(operation_id, libc::SIGINT, Self::TERMINATE_TIMEOUT).await;
+        if !terminated {
+            terminated = self.signal_until_finished(operation_id, libc::SIGKILL, Self::TERMINATE_TIMEOUT).await;
+        }
+
+        let lock_released = wait_for_lock_release(Self::PISI_LOCK_PATH, Self::LOCK_TIMEOUT).await;
+        let consistent = terminated && lock_released && !phase.modifies_system();
+
+        let message = if !terminated {
+            "pisi did not exit after SIGKILL".to_string()
+        } else if !lock_released {
+            "pisi exited but its lock is still held".to_string()
+        } else if consistent {
+            "Operation cancelled before the system was modified".to_string()
+        } else {
+            "Operation cancelled while packages were being changed; run `pisi configure-pending` and `pisi check` to verify the system".to_string()
+        };
+
+        Ok(CancelReport {
+            operation_id: operation_id.to_string(),
+            terminated,
+            lock_released,
+            consistent,
+            phase,
+            message,
+        })
+    }
+
+    /// İşlemin kaydı silinene kadar bekler. İptal istenmeden hemen önce başlamış bir
+    /// pisi süreci de olabileceği için kayıtta görülen her yeni pid'e `signal` gönderilir.
+    async fn signal_until_finished(&self, operation_id: &str, signal: libc::c_int, timeout: Duration) -> bool {
+        let start = Instant::now();
+        let mut signalled = 0;
+        while let Some(pid) = self.pid(operation_id) {
+            if pid != signalled {
+                send_signal(pid, signal).await;
+                signalled = pid;
+            }
+            if start.elapsed() > timeout {
+                return false;
+            }
+            tokio::time::sleep(Self::POLL_INTERVAL).await;
+        }
+        true
+    }
+}
+
+/// pid süreç kaydından geldiği için sinyal yalnızca kendi başlattığımız pisi'ye gider.
+/// pkexec ile root olarak çalışan sürece yetkimiz yetmezse (EPERM) yardımcı üzerinden gönderilir.
+async fn send_signal(pid: u32, signal: libc::c_int) {
+    // 0 süreç grubunun tamamına gider; kayıtta geçerli pid yoksa hiçbir şey yapılmaz
+    if pid == 0 {
+        return;
+    }
+    if unsafe { libc::kill(pid as libc::pid_t, signal) } == 0 {
+        return;
+    }
+    if std::io::Error::last_os_error().raw_os_error() != Some(libc::EPERM) {
+        return;
+    }
+
+    let name = if signal == libc::SIGKILL { "KILL" } else { "INT" };
+    match Command::from(privileged::signal_command(pid, name)).status().await {
+        Ok(status) if status.success() => {}
+        Ok(status) => println!("Helper failed to signal pid {}: {}", pid, status),
+        Err(e) => println!("Failed to run helper to signal pid {}: {}", pid, e),
+    }
+}
+
+/// Kilidi kısa süreliğine kendimiz almayı deneyerek pisi'nin bırakıp bırakmadığını anlar
+fn is_lock_free(path: &str) -> bool {
+    let file = match File::open(path) {
+        Ok(file) => file,
+        // Kilit dosyası yoksa tutan da yoktur
+        Err(_) => return true,
+    };
+    let fd = file.as_raw_fd();
+    let acquired = unsafe { libc::flock(fd, libc::LOCK_EX | libc::LOCK_NB) } == 0;
+    if acquired {
+        unsafe {
+            libc::flock(fd, libc::LOCK_UN);
+        }
+    }
+    acquired
+}
+
+async fn wait_for_lock_release(path: &str, timeout: Duration) -> bool {
+    let start = Instant::now();
+    while !is_lock_free(path) {
+        if start.elapsed() > timeout {
+            return false;
+        }
+        tokio::time::sleep(OperationRegistry::POLL_INTERVAL).await;
+    }
+    true
+}
+
+/// pisi'yi `runner` ile çalıştırır, çıktıdaki ilerleme satırlarını `on_progress` ile bildirir.
+/// Süreç çalıştığı sürece `registry` içinde kayıtlı kalır, böylece
+/// `OperationRegistry::cancel` ile durdurulabilir. İşlem daha önce `OperationRegistry::begin`
+/// ile kaydedilmişse kayıt silinmez; iptal istenmişse pisi hiç çalıştırılmaz.
+/// Başarısızlıkta stderr sınıflandırılarak döner.
+pub async fn run_pisi_with_progress(
+    runner: &dyn PisiRunner,
+    registry: &OperationRegistry,
+    operation_id: &str,
+    args: &[&str],
+    mut on_progress: impl FnMut(OperationProgress) + Send,
+) -> Result<(), PmError> {
+    let command = format!("pisi {}", args.join(" "));
+    let owned = registry.begin(operation_id, &command);
+    if registry.is_cancel_requested(operation_id) {
+        if owned {
+            registry.finish(operation_id);
+        }
+        on_progress(OperationProgress::new(operation_id, OperationPhase::Cancelled, "Cancelled by user"));
+        return Err(PmError::Cancelled);
+    }
+    on_progress(OperationProgress::new(operation_id, OperationPhase::Started, command.clone()));
+
+    let output = runner
+        .stream(
+            args,
+            &mut |pid| registry.set_process(operation_id, &command, pid),
+            &mut |line| {
+                if let Some(progress) = parse_progress_line(operation_id, line) {
+                    registry.set_phase(operation_id, progress.phase);
+                    on_progress(progress);
+                }
+            },
+        )
+        .await;
+
+    let cancelled = if owned {
+        registry.finish(operation_id)
+    } else {
+        registry.set_process(operation_id, &command, 0);
+        registry.is_cancel_requested(operation_id)
+    };
+    let output = output?;
+
+    if cancelled {
+        on_progress(OperationProgress::new(operation_id, OperationPhase::Cancelled, "Cancelled by user"));
+        Err(PmError::Cancelled)
+    } else if output.is_success() {
+        on_progress(OperationProgress::new(operation_id, OperationPhase::Finished, ""));
+        Ok(())
+    } else {
+        on_progress(OperationProgress::new(operation_id, OperationPhase::Failed, output.stderr.trim()));
+        Err(PmError::from_pisi_output(&output))
+    }
+}
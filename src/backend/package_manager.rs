@@ -1,130 +1,329 @@
-use std::process::Command;
+use crate::config::settings::AppSettings;
 use crate::backend::xml_parser::{PackageInfo, Component, XmlParser};
+use crate::backend::installed_db::{InstalledDb, InstalledFile, InstalledPackage};
+use crate::backend::resolver::{Resolver, TransactionPlan};
+use crate::backend::reverse_deps::{ReverseDependency, ReverseDependencyMap};
+use crate::backend::operation::{CancelReport, OperationProgress, OperationRegistry, RunningOperation, PROGRESS_EVENT};
+use crate::backend::actions::{PackageActions, UpgradablePackage};
+use crate::backend::names::{PackageName, RepoName};
+use crate::backend::repository::{MoveDirection, RepositoryInfo, RepositoryManager};
+use crate::backend::repo_probe::{self, RepositoryProbe, SystemDistribution};
+use crate::backend::mirrors::{self, Mirror, MirrorReport};
+use crate::backend::error::PmError;
+use crate::backend::compression;
+use crate::backend::runner::PisiRunner;
+use crate::backend::transaction::{TransactionAction, TransactionResult};
 use base64::{Engine as _, engine::general_purpose};
+use tauri::{AppHandle, Emitter, State};
+
+/// Tauri managed state'teki pisi çalıştırıcısı
+pub type RunnerState<'r> = State<'r, Box<dyn PisiRunner>>;
+
+fn package_actions<'a>(runner: &RunnerState<'a>, registry: &'a OperationRegistry) -> PackageActions<'a> {
+    PackageActions::new(runner.inner().as_ref(), registry, InstalledDb::system())
+}
 
 #[tauri::command]
-pub async fn get_packages() -> Result<Vec<PackageInfo>, String> {
-    XmlParser::load_pisi_index().map_err(|e| e.to_string())
+pub async fn get_packages() -> Result<Vec<PackageInfo>, PmError> {
+    Ok(XmlParser::load_pisi_index()?)
 }
 
 #[tauri::command]
-pub async fn get_components() -> Result<Vec<Component>, String> {
-    XmlParser::get_components().map_err(|e| e.to_string())
+pub async fn get_components() -> Result<Vec<Component>, PmError> {
+    Ok(XmlParser::get_components()?)
 }
 
 #[tauri::command]
-pub async fn search_packages(query: String) -> Result<Vec<PackageInfo>, String> {
-    let packages = XmlParser::load_pisi_index().map_err(|e| e.to_string())?;
+pub async fn search_packages(query: String) -> Result<Vec<PackageInfo>, PmError> {
+    let packages = XmlParser::load_pisi_index()?;
     let query = query.to_lowercase();
     Ok(packages.into_iter()
         .filter(|p| p.name.to_lowercase().contains(&query) || p.summary.to_lowercase().contains(&query))
         .collect())
 }
 
+/// Depo eklemeden önce yerel bir indeks dosyasını önizler; sadece indeks uzantılı
+/// normal dosyalar okunur
 #[tauri::command]
-pub async fn get_installed_packages() -> Result<Vec<String>, String> {
-    let output = Command::new("pisi")
-        .arg("li")
-        .output()
-        .map_err(|e| e.to_string())?;
+pub async fn preview_index_file(path: String) -> Result<Vec<PackageInfo>, PmError> {
+    let path = compression::checked_index_path(std::path::Path::new(&path))?;
+    Ok(XmlParser::load_index_file(&path)?)
+}
 
-    if output.status.success() {
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let installed = stdout.lines()
-            .map(|l| l.split_whitespace().next().unwrap_or("").to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
-        Ok(installed)
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("pisi li failed: {}", stderr))
-    }
+#[tauri::command]
+pub async fn get_installed_packages() -> Result<Vec<String>, PmError> {
+    Ok(InstalledDb::system().names()?)
 }
 
 #[tauri::command]
-pub async fn install_package(package_name: String) -> Result<String, String> {
-    let output = Command::new("pisi")
-        .args(["it", &package_name, "-y"])
-        .output()
-        .map_err(|e| e.to_string())?;
+pub async fn get_installed_package_details() -> Result<Vec<InstalledPackage>, PmError> {
+    Ok(InstalledDb::system().list()?)
+}
 
-    if output.status.success() {
-        Ok(format!("Package {} installed successfully", package_name))
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Failed to install {}: {}", package_name, stderr))
-    }
+#[tauri::command]
+pub async fn get_installed_files(package_name: PackageName) -> Result<Vec<InstalledFile>, PmError> {
+    Ok(InstalledDb::system().files(package_name.as_str())?)
 }
 
+/// Kurulumdan önce kullanıcıya gösterilecek planı (ek paketler, boyutlar, kaldırılacaklar) döner
 #[tauri::command]
-pub async fn remove_package(package_name: String) -> Result<String, String> {
-    let output = Command::new("pisi")
-        .args(["rm", &package_name, "-y"])
-        .output()
-        .map_err(|e| e.to_string())?;
+pub async fn get_install_plan(package_names: Vec<PackageName>) -> Result<TransactionPlan, PmError> {
+    let index = XmlParser::load_pisi_index()?;
+    let installed = InstalledDb::system().list()?;
+    Resolver::new(&index, &installed).plan_install(&package_names)
+}
+
+#[tauri::command]
+pub async fn install_package(
+    app: AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+    package_name: PackageName,
+) -> Result<String, PmError> {
+    package_actions(&runner, &registry)
+        .install(&package_name, |progress| emit_progress(&app, progress))
+        .await
+}
 
-    if output.status.success() {
-        Ok(format!("Package {} removed successfully", package_name))
+#[tauri::command]
+pub async fn get_reverse_dependencies(
+    package_name: PackageName,
+    installed_only: bool,
+    recursive: bool,
+) -> Result<Vec<ReverseDependency>, PmError> {
+    let installed = InstalledDb::system().list()?;
+    let map = if installed_only {
+        ReverseDependencyMap::from_installed(&installed)
     } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Failed to remove {}: {}", package_name, stderr))
+        let index = XmlParser::load_pisi_index()?;
+        ReverseDependencyMap::from_index(&index, &installed)
+    };
+    Ok(map.query(package_name.as_str(), installed_only, recursive))
+}
+
+/// Paketi kaldırır. Kurulu başka paketler bu pakete bağımlıysa `force` verilmedikçe
+/// kaldırmayı reddeder ve etkilenecek paketleri hata mesajında listeler.
+#[tauri::command]
+pub async fn remove_package(
+    app: AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+    package_name: PackageName,
+    force: Option<bool>,
+) -> Result<String, PmError> {
+    package_actions(&runner, &registry)
+        .remove(&package_name, force.unwrap_or(false), |progress| emit_progress(&app, progress))
+        .await
+}
+
+#[tauri::command]
+pub async fn update_package(
+    app: AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+    package_name: PackageName,
+) -> Result<String, PmError> {
+    package_actions(&runner, &registry)
+        .update(&package_name, |progress| emit_progress(&app, progress))
+        .await
+}
+
+/// Birden fazla kurma/kaldırma/yükseltme işlemini tek seferde uygular ve paket başına sonuç döner
+#[tauri::command]
+pub async fn run_transaction(
+    app: AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+    actions: Vec<TransactionAction>,
+    force: Option<bool>,
+) -> Result<TransactionResult, PmError> {
+    package_actions(&runner, &registry)
+        .run_transaction(&actions, force.unwrap_or(false), |progress| emit_progress(&app, progress))
+        .await
+}
+
+/// İlerleme bildirimini arayüze Tauri olayı olarak gönderir
+fn emit_progress(app: &AppHandle, progress: OperationProgress) {
+    if let Err(e) = app.emit(PROGRESS_EVENT, progress) {
+        println!("Failed to emit progress event: {:?}", e);
     }
 }
 
 #[tauri::command]
-pub async fn update_package(package_name: String) -> Result<String, String> {
-    let output = Command::new("pisi")
-        .args(["up", &package_name, "-y"])
-        .output()
-        .map_err(|e| e.to_string())?;
+pub async fn get_upgradable_packages(
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+) -> Result<Vec<UpgradablePackage>, PmError> {
+    package_actions(&runner, &registry)
+        .upgradable(&AppSettings::load().held_packages)
+        .await
+}
 
-    if output.status.success() {
-        Ok(format!("Package {} updated successfully", package_name))
-    } else {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Failed to update {}: {}", package_name, stderr))
+/// Sabitlenmiş paketler dışındaki tüm yükseltmeleri uygular
+#[tauri::command]
+pub async fn upgrade_all(
+    app: AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+) -> Result<TransactionResult, PmError> {
+    let held = AppSettings::load().held_packages;
+    // Sabitli paket yoksa bağımlılık kontrolü yapılmaz, indeks de yüklenmez
+    let index = if held.is_empty() { Vec::new() } else { XmlParser::load_pisi_index()? };
+    package_actions(&runner, &registry)
+        .upgrade_all(&index, &held, |progress| emit_progress(&app, progress))
+        .await
+}
+
+#[tauri::command]
+pub async fn get_held_packages() -> Result<Vec<String>, PmError> {
+    Ok(AppSettings::load().held_packages)
+}
+
+#[tauri::command]
+pub async fn hold_package(package_name: PackageName) -> Result<Vec<String>, PmError> {
+    let mut settings = AppSettings::load();
+    if settings.hold(package_name.as_str()) {
+        settings.save()?;
     }
+    Ok(settings.held_packages)
 }
 
 #[tauri::command]
-pub async fn get_upgradable_packages() -> Result<Vec<String>, String> {
-    let output = Command::new("pisi")
-        .args(["list-upgrades"])
-        .output()
-        .map_err(|e| e.to_string())?;
+pub async fn unhold_package(package_name: PackageName) -> Result<Vec<String>, PmError> {
+    let mut settings = AppSettings::load();
+    if settings.unhold(package_name.as_str()) {
+        settings.save()?;
+    }
+    Ok(settings.held_packages)
+}
 
-    if output.status.success() {
-        let output_str = String::from_utf8_lossy(&output.stdout);
-        let packages: Vec<String> = output_str
-            .lines()
-            .map(|line| line.split_whitespace().next().unwrap_or("").to_string())
-            .filter(|s| !s.is_empty())
-            .collect();
-        Ok(packages)
-    } else {
-        let error = String::from_utf8_lossy(&output.stderr);
-        Err(format!("Failed to get upgradable packages: {}", error))
+#[tauri::command]
+pub async fn update_repo(
+    app: AppHandle,
+    runner: RunnerState<'_>,
+    registry: State<'_, OperationRegistry>,
+) -> Result<(), PmError> {
+    package_actions(&runner, &registry)
+        .update_repo(|progress| emit_progress(&app, progress))
+        .await
+}
+
+#[tauri::command]
+pub async fn list_repositories(runner: RunnerState<'_>) -> Result<Vec<RepositoryInfo>, PmError> {
+    RepositoryManager::new(runner.inner().as_ref()).list_repositories().await
+}
+
+/// Depo eklenmeden önce adresi denetler ve indeksin bu sistemle uyumunu raporlar
+#[tauri::command]
+pub async fn probe_repository(url: String) -> Result<RepositoryProbe, PmError> {
+    repo_probe::probe_index(&url, &SystemDistribution::system()).await
+}
+
+/// `position` verilirse depo o sıraya (0 en yüksek öncelik), verilmezse sona eklenir
+#[tauri::command]
+pub async fn add_repository(
+    runner: RunnerState<'_>,
+    name: RepoName,
+    url: String,
+    position: Option<usize>,
+) -> Result<(), PmError> {
+    let manager = RepositoryManager::new(runner.inner().as_ref());
+    let system = SystemDistribution::system();
+    match position {
+        Some(position) => manager.add_repository_at(&name, &url, position, &system).await?,
+        None => manager.add_repository(&name, &url, &system).await?,
     }
+    XmlParser::clear_memory_cache();
+    Ok(())
+}
+
+/// Depoyu bir adım yukarı/aşağı taşır; aynı paketi sunan depolardan sırada önce gelen kazanır
+#[tauri::command]
+pub async fn move_repository(
+    runner: RunnerState<'_>,
+    name: RepoName,
+    direction: MoveDirection,
+) -> Result<Vec<RepositoryInfo>, PmError> {
+    let repos = RepositoryManager::new(runner.inner().as_ref()).move_repository(&name, direction).await?;
+    XmlParser::clear_memory_cache();
+    Ok(repos)
+}
+
+#[tauri::command]
+pub async fn remove_repository(runner: RunnerState<'_>, name: RepoName) -> Result<(), PmError> {
+    RepositoryManager::new(runner.inner().as_ref()).remove_repository(&name).await?;
+    XmlParser::clear_memory_cache();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn enable_repository(runner: RunnerState<'_>, name: RepoName) -> Result<(), PmError> {
+    RepositoryManager::new(runner.inner().as_ref()).enable_repository(&name).await?;
+    XmlParser::clear_memory_cache();
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn disable_repository(runner: RunnerState<'_>, name: RepoName) -> Result<(), PmError> {
+    RepositoryManager::new(runner.inner().as_ref()).disable_repository(&name).await?;
+    XmlParser::clear_memory_cache();
+    Ok(())
 }
 
+/// Yansı listesindeki yansıları hız testinden geçirir. Son 24 saatte aynı liste için yapılmış
+/// bir test varsa `force` verilmedikçe onun sonucu döner.
 #[tauri::command]
-pub async fn update_repo() -> Result<(), String> {
-    let output = Command::new("pisi")
-        .args(["ur"])
-        .output()
-        .map_err(|e| e.to_string())?;
+pub async fn test_mirrors(force: Option<bool>) -> Result<MirrorReport, PmError> {
+    let settings = AppSettings::load();
+    mirrors::mirror_report(std::path::Path::new(&settings.mirror_list), MirrorReport::cache_path().as_deref(), force.unwrap_or(false)).await
+}
 
-    if !output.status.success() {
-        let err = String::from_utf8_lossy(&output.stderr);
-        return Err(format!("Pisi update-repo failed: {}", err));
+/// Yansı listesindeki kayıtlar; arayüz sadece yansısı olan depolara geçiş düğmesi gösterir
+#[tauri::command]
+pub async fn list_mirrors() -> Result<Vec<Mirror>, PmError> {
+    mirrors::load_mirror_list(std::path::Path::new(&AppSettings::load().mirror_list))
+}
+
+/// Deponun adresini, kendi yansıları arasında hız testinde en iyi sonucu veren yansıyla değiştirir
+#[tauri::command]
+pub async fn use_fastest_mirror(runner: RunnerState<'_>, name: RepoName) -> Result<Vec<RepositoryInfo>, PmError> {
+    let report = test_mirrors(Some(false)).await?;
+    if !report.results.iter().any(|r| r.mirror.repository == name.as_str()) {
+        return Err(PmError::InvalidRequest { message: format!("No mirrors are listed for repository {}", name) });
     }
+    let fastest = report.fastest_for(name.as_str()).ok_or_else(|| PmError::NetworkUnavailable {
+        message: format!("None of the mirrors of {} is reachable", name),
+    })?;
+
+    let repos = RepositoryManager::new(runner.inner().as_ref())
+        .switch_mirror(&name, &fastest.url, &SystemDistribution::system())
+        .await?;
+    XmlParser::clear_memory_cache();
+    Ok(repos)
+}
+
+/// `name` verilirse sadece o depoyu, verilmezse tüm depoları günceller
+#[tauri::command]
+pub async fn update_repositories(runner: RunnerState<'_>, name: Option<RepoName>) -> Result<(), PmError> {
+    RepositoryManager::new(runner.inner().as_ref()).update_repositories(name.as_ref()).await?;
+    XmlParser::clear_memory_cache();
     Ok(())
 }
 
 #[tauri::command]
-pub async fn get_package_icon(icon_name: String) -> Result<String, String> {
+pub async fn get_running_operations(registry: State<'_, OperationRegistry>) -> Result<Vec<RunningOperation>, PmError> {
+    Ok(registry.list())
+}
+
+#[tauri::command]
+pub async fn cancel_operation(registry: State<'_, OperationRegistry>, operation_id: String) -> Result<CancelReport, PmError> {
+    registry.cancel(&operation_id).await
+}
+
+#[tauri::command]
+pub async fn get_package_icon(icon_name: String) -> Result<String, PmError> {
     if icon_name.is_empty() {
-        return Err("Icon name is empty".to_string());
+        return Err(PmError::InvalidRequest { message: "Icon name is empty".to_string() });
     }
 
     let paths = [
@@ -136,11 +335,11 @@ pub async fn get_package_icon(icon_name: String) -> Result<String, String> {
     for path_str in paths {
         let path = std::path::Path::new(&path_str);
         if path.exists() {
-            let data = std::fs::read(path).map_err(|e| e.to_string())?;
+            let data = std::fs::read(path).map_err(|e| PmError::Internal { message: e.to_string() })?;
             let b64 = general_purpose::STANDARD.encode(data);
             return Ok(format!("data:image/png;base64,{}", b64));
         }
     }
 
-    Err("Icon not found".to_string())
+    Err(PmError::Internal { message: "Icon not found".to_string() })
 }
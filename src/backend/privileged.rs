@@ -0,0 +1,141 @@
+use anyhow::Result;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// Kurulu yardımcı programın yolu (polkit politikasındaki `exec.path` ile aynı olmalı)
+pub const HELPER_PATH: &str = "/usr/libexec/pisi-pm-helper";
+
+/// Geliştirme ve testlerde yardımcı programın yerini değiştirmek için ortam değişkeni
+pub const HELPER_PATH_ENV: &str = "PM_PISI_HELPER";
+
+/// `data/org.pisilinux.pm-gui.policy` içindeki polkit eylemi
+pub const POLKIT_ACTION: &str = "org.pisilinux.pm-gui.pisi-helper";
+
+/// Yardımcının pisi'ye iletmeyi kabul ettiği (sistemi değiştiren) komutlar
+pub const ALLOWED_VERBS: &[&str] = &[
+    "install", "it",
+    "remove", "rm",
+    "upgrade", "up",
+    "update-repo", "ur",
+    "add-repo", "ar",
+    "remove-repo", "rr",
+    "enable-repo", "er",
+    "disable-repo", "dr",
+];
+
+/// Yardımcının kendi işlediği, pisi'ye gitmeyen komut: `signal <INT|KILL> <pid>`.
+/// Root olarak çalışan pisi'yi arayüz süreci kendi başına durduramadığı için iptalde kullanılır.
+pub const SIGNAL_VERB: &str = "signal";
+
+/// Değer almayan izinli seçenekler
+const ALLOWED_FLAGS: &[&str] = &["-y", "--yes-all", "--no-fetch"];
+
+/// Sayısal değer alan izinli seçenekler (`add-repo --at 0`)
+const ALLOWED_VALUE_OPTIONS: &[&str] = &["--at"];
+
+/// Seçenekleri ve adları `--` ile ayırarak pisi argümanlarını oluşturur:
+/// `pisi_args("it", &["-y"], &["nano"])` -> `it -y -- nano`.
+/// `--` sonrasındaki hiçbir şey pisi tarafından seçenek olarak yorumlanmaz.
+pub fn pisi_args<'a>(verb: &'a str, options: &[&'a str], operands: &[&'a str]) -> Vec<&'a str> {
+    let mut args = Vec::with_capacity(options.len() + operands.len() + 2);
+    args.push(verb);
+    args.extend_from_slice(options);
+    if !operands.is_empty() {
+        args.push("--");
+        args.extend_from_slice(operands);
+    }
+    args
+}
+
+pub fn helper_path() -> PathBuf {
+    std::env::var_os(HELPER_PATH_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(HELPER_PATH))
+}
+
+/// `pkexec <helper> <args...>` komutunu hazırlar. Arayüz yetkisiz çalışır;
+/// yetki polkit üzerinden sadece bu yardımcı için istenir.
+pub fn pisi_command(args: &[&str]) -> Command {
+    helper_command(&helper_path(), args)
+}
+
+/// `pisi_command` ile aynı, yardımcı program verilen yoldan çalıştırılır
+pub fn helper_command(helper: &Path, args: &[&str]) -> Command {
+    let mut command = Command::new("pkexec");
+    command.arg(helper).args(args);
+    command
+}
+
+/// Root sahipli pisi sürecine sinyali yardımcı üzerinden gönderir
+pub fn signal_command(pid: u32, signal: &str) -> Command {
+    pisi_command(&[SIGNAL_VERB, signal, &pid.to_string()])
+}
+
+/// Yardımcıya gelen argümanları doğrular. Sadece izinli komutlar ve seçenekler geçer;
+/// paket/depo adları `--` ayıracından sonra gelmelidir ve yine de `-` ile başlayamaz.
+pub fn validate_helper_args(args: &[String]) -> Result<()> {
+    let (verb, rest) = args
+        .split_first()
+        .ok_or_else(|| anyhow::anyhow!("No command given"))?;
+
+    if verb == SIGNAL_VERB {
+        return parse_signal_args(rest).map(|_| ());
+    }
+    if !ALLOWED_VERBS.contains(&verb.as_str()) {
+        return Err(anyhow::anyhow!("Command not allowed: {}", verb));
+    }
+
+    let mut iter = rest.iter();
+    let mut operands = false;
+    while let Some(arg) = iter.next() {
+        if operands {
+            if arg.is_empty() || arg.starts_with('-') || arg.chars().any(char::is_control) {
+                return Err(anyhow::anyhow!("Invalid argument: {:?}", arg));
+            }
+            continue;
+        }
+        if arg == "--" {
+            operands = true;
+            continue;
+        }
+        if ALLOWED_FLAGS.contains(&arg.as_str()) {
+            continue;
+        }
+        if ALLOWED_VALUE_OPTIONS.contains(&arg.as_str()) {
+            let value = iter.next().ok_or_else(|| anyhow::anyhow!("{} requires a value", arg))?;
+            value.parse::<u32>().map_err(|_| anyhow::anyhow!("Invalid value for {}: {}", arg, value))?;
+            continue;
+        }
+        if arg.starts_with('-') {
+            return Err(anyhow::anyhow!("Option not allowed: {}", arg));
+        }
+        return Err(anyhow::anyhow!("Missing `--` before {:?}", arg));
+    }
+    Ok(())
+}
+
+/// `signal` komutunun argümanları: (sinyal numarası, pid)
+pub fn parse_signal_args(args: &[String]) -> Result<(libc::c_int, u32)> {
+    let [signal, pid] = args else {
+        return Err(anyhow::anyhow!("Usage: signal <INT|KILL> <pid>"));
+    };
+    let signal = match signal.as_str() {
+        "INT" => libc::SIGINT,
+        "KILL" => libc::SIGKILL,
+        other => return Err(anyhow::anyhow!("Signal not allowed: {}", other)),
+    };
+    let pid = pid.parse::<u32>().map_err(|_| anyhow::anyhow!("Invalid pid: {}", pid))?;
+    Ok((signal, pid))
+}
+
+/// Sinyal sadece komut satırında pisi geçen süreçlere gönderilebilir
+pub fn is_pisi_process(pid: u32) -> bool {
+    std::fs::read(format!("/proc/{}/cmdline", pid))
+        .map(|cmdline| {
+            cmdline
+                .split(|&b| b == 0)
+                .take(2)
+                .any(|arg| arg.rsplit(|&b| b == b'/').next() == Some(b"pisi"))
+        })
+        .unwrap_or(false)
+}
@@ -0,0 +1,264 @@
+use anyhow::Result;
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use serde::{Serialize, Deserialize};
+use std::fs::{self, File};
+use std::io::{Cursor, Read};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use crate::backend::compression::{open_index_reader, IndexCompression};
+use crate::backend::error::PmError;
+
+/// Ön kontrolde indeksin sadece baş kısmı okunur; `<Distribution>` bloğu en başta yer alır
+pub const PROBE_HEAD_BYTES: usize = 256 * 1024;
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+const PISI_CONF_PATH: &str = "/etc/pisi/pisi.conf";
+/// PiSi depo adresinin gösterebileceği indeks dosyaları
+const INDEX_FILE_NAMES: [&str; 4] = ["pisi-index.xml", "pisi-index.xml.xz", "pisi-index.xml.zst", "pisi-index.xml.gz"];
+
+/// Depo adresinin türü
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RepositoryUrl {
+    Http(String),
+    Local(PathBuf),
+}
+
+impl RepositoryUrl {
+    /// Adresin şemasını ve bir PiSi indeks dosyasını gösterip göstermediğini denetler.
+    /// `http://`, `https://`, `file://` ve mutlak yerel yollar kabul edilir.
+    pub fn parse(url: &str) -> Result<Self, PmError> {
+        let url = url.trim();
+        let invalid = |reason: &str| PmError::InvalidRequest { message: format!("Invalid repository URL {}: {}", url, reason) };
+
+        if url.contains(char::is_whitespace) {
+            return Err(invalid("contains whitespace"));
+        }
+
+        let parsed = if url.starts_with("http://") || url.starts_with("https://") {
+            let host = url.split("://").nth(1).and_then(|rest| rest.split('/').next()).unwrap_or_default();
+            if host.is_empty() {
+                return Err(invalid("missing host"));
+            }
+            RepositoryUrl::Http(url.to_string())
+        } else if let Some(path) = url.strip_prefix("file://") {
+            RepositoryUrl::Local(PathBuf::from(path))
+        } else if url.starts_with('/') {
+            RepositoryUrl::Local(PathBuf::from(url))
+        } else {
+            return Err(invalid("only http, https and file URLs are supported"));
+        };
+
+        if !INDEX_FILE_NAMES.iter().any(|name| url.ends_with(&format!("/{}", name))) {
+            return Err(invalid("must point to a pisi-index.xml file"));
+        }
+        Ok(parsed)
+    }
+
+    fn file_name(&self) -> &str {
+        match self {
+            RepositoryUrl::Http(url) => url.rsplit('/').next().unwrap_or_default(),
+            RepositoryUrl::Local(path) => path.file_name().and_then(|n| n.to_str()).unwrap_or_default(),
+        }
+    }
+}
+
+/// Bu sistemin PiSi dağıtım bilgisi (`/etc/pisi/pisi.conf` `[general]` bölümü)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SystemDistribution {
+    pub distribution: Option<String>,
+    pub release: Option<String>,
+    pub architecture: Option<String>,
+}
+
+impl SystemDistribution {
+    pub fn system() -> Self {
+        Self::from_pisi_conf(Path::new(PISI_CONF_PATH))
+    }
+
+    /// Dosya yoksa veya alan eksikse ilgili karşılaştırma yapılmaz
+    pub fn from_pisi_conf(path: &Path) -> Self {
+        let mut result = Self::default();
+        let Ok(content) = fs::read_to_string(path) else {
+            return result;
+        };
+
+        let mut in_general = false;
+        for line in content.lines().map(str::trim) {
+            if line.starts_with('#') {
+                continue;
+            }
+            if line.starts_with('[') {
+                in_general = line == "[general]";
+                continue;
+            }
+            let Some((key, value)) = line.split_once('=') else { continue };
+            if !in_general {
+                continue;
+            }
+            let value = Some(value.trim().to_string()).filter(|v| !v.is_empty());
+            match key.trim() {
+                "distribution" => result.distribution = value,
+                "distribution_release" => result.release = value,
+                "architecture" => result.architecture = value,
+                _ => {}
+            }
+        }
+        result
+    }
+}
+
+/// İndeksteki ve sistemdeki değeri farklı olan alan
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ProbeMismatch {
+    /// `distribution`, `release` veya `architecture`
+    pub field: String,
+    pub expected: String,
+    pub found: String,
+}
+
+/// Depo eklenmeden önce indeksin baş kısmından okunan bilgiler
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepositoryProbe {
+    pub url: String,
+    pub distribution: Option<String>,
+    pub release: Option<String>,
+    pub architecture: Option<String>,
+    /// Boşsa depo bu sistemle uyumlu görünüyor
+    pub mismatches: Vec<ProbeMismatch>,
+}
+
+/// Adresi doğrular, indeksin başını indirir (veya yerel dosyadan okur), geçerli bir PiSi
+/// indeksi olduğunu denetler ve sistemle uyuşmayan dağıtım/mimari bilgilerini raporlar
+pub async fn probe_index(url: &str, system: &SystemDistribution) -> Result<RepositoryProbe, PmError> {
+    let parsed = RepositoryUrl::parse(url)?;
+    let head = match &parsed {
+        RepositoryUrl::Http(url) => fetch_head(url).await?,
+        RepositoryUrl::Local(path) => read_head(path)?,
+    };
+
+    let hint = IndexCompression::from_extension(Path::new(parsed.file_name()));
+    let distribution = parse_index_head(&head, hint).map_err(|e| PmError::InvalidRequest {
+        message: format!("{} is not a PiSi repository index: {}", url, e),
+    })?;
+
+    let mut mismatches = Vec::new();
+    for (field, expected, found) in [
+        ("distribution", &system.distribution, &distribution.distribution),
+        ("release", &system.release, &distribution.release),
+        ("architecture", &system.architecture, &distribution.architecture),
+    ] {
+        if let (Some(expected), Some(found)) = (expected, found) {
+            if expected != found {
+                mismatches.push(ProbeMismatch {
+                    field: field.to_string(),
+                    expected: expected.clone(),
+                    found: found.clone(),
+                });
+            }
+        }
+    }
+
+    Ok(RepositoryProbe {
+        url: url.trim().to_string(),
+        distribution: distribution.distribution,
+        release: distribution.release,
+        architecture: distribution.architecture,
+        mismatches,
+    })
+}
+
+/// İndeksin ilk `PROBE_HEAD_BYTES` baytını indirir
+async fn fetch_head(url: &str) -> Result<Vec<u8>, PmError> {
+    let network_error = |e: reqwest::Error| PmError::NetworkUnavailable { message: format!("Could not fetch {}: {}", url, e) };
+
+    let client = reqwest::Client::builder()
+        .timeout(PROBE_TIMEOUT)
+        .build()
+        .map_err(|e| PmError::Internal { message: e.to_string() })?;
+    let mut response = client
+        .get(url)
+        .header(reqwest::header::RANGE, format!("bytes=0-{}", PROBE_HEAD_BYTES - 1))
+        .send()
+        .await
+        .map_err(network_error)?;
+
+    let status = response.status();
+    if status == reqwest::StatusCode::NOT_FOUND {
+        return Err(PmError::InvalidRequest { message: format!("Repository index not found at {} (HTTP 404)", url) });
+    }
+    if !status.is_success() {
+        return Err(PmError::NetworkUnavailable { message: format!("Could not fetch {}: HTTP {}", url, status) });
+    }
+
+    // Sunucu Range başlığını yok sayıp tüm dosyayı gönderse de en fazla PROBE_HEAD_BYTES okunur
+    let mut head = Vec::new();
+    while head.len() < PROBE_HEAD_BYTES {
+        match response.chunk().await.map_err(network_error)? {
+            Some(chunk) => head.extend_from_slice(&chunk),
+            None => break,
+        }
+    }
+    head.truncate(PROBE_HEAD_BYTES);
+    Ok(head)
+}
+
+fn read_head(path: &Path) -> Result<Vec<u8>, PmError> {
+    let file = File::open(path).map_err(|e| PmError::InvalidRequest {
+        message: format!("Could not open repository index {}: {}", path.display(), e),
+    })?;
+    let mut head = Vec::new();
+    file.take(PROBE_HEAD_BYTES as u64)
+        .read_to_end(&mut head)
+        .map_err(|e| PmError::Internal { message: e.to_string() })?;
+    Ok(head)
+}
+
+/// İndeksin baş kısmından (gerekirse açarak) `<PISI>` kök elemanını ve `<Distribution>` bloğunu okur.
+/// Baş kısım kesik olduğu için `<Distribution>` bittikten sonra okuma durdurulur.
+pub fn parse_index_head(head: &[u8], hint: IndexCompression) -> Result<SystemDistribution> {
+    let reader = open_index_reader(Cursor::new(head), hint)?;
+    let mut reader = Reader::from_reader(reader);
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut result = SystemDistribution::default();
+
+    loop {
+        let event = reader
+            .read_event_into(&mut buf)
+            .map_err(|e| anyhow::anyhow!("could not read index head: {}", e))?;
+        match event {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                if path.is_empty() && name != "PISI" {
+                    return Err(anyhow::anyhow!("root element is <{}>, expected <PISI>", name));
+                }
+                if path.len() == 1 && name != "Distribution" {
+                    return Err(anyhow::anyhow!("<{}> found before the <Distribution> block", name));
+                }
+                path.push(name);
+            }
+            Event::Text(text) if path.len() == 3 => {
+                let value = Some(text.decode()?.trim().to_string()).filter(|v| !v.is_empty());
+                match path[2].as_str() {
+                    "SourceName" => result.distribution = value,
+                    "Version" => result.release = value,
+                    "Architecture" => result.architecture = value,
+                    _ => {}
+                }
+            }
+            Event::End(_) => {
+                let closed = path.pop();
+                if closed.as_deref() == Some("Distribution") {
+                    return Ok(result);
+                }
+            }
+            Event::Empty(e) if path.is_empty() => {
+                let name = String::from_utf8_lossy(e.local_name().as_ref()).to_string();
+                return Err(anyhow::anyhow!("root element is <{}>, expected <PISI>", name));
+            }
+            Event::Eof => return Err(anyhow::anyhow!("no <Distribution> block")),
+            _ => {}
+        }
+        buf.clear();
+    }
+}
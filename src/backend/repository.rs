@@ -1,104 +1,380 @@
-use anyhow::Result;
-use std::process::Command;
+use serde::{Serialize, Deserialize};
+use std::fs;
+use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
+use crate::backend::compression::open_index;
+use crate::backend::error::PmError;
+use crate::backend::index_stream::count_packages;
+use crate::backend::index_verify::{IndexVerifier, TrustStatus};
+use crate::backend::names::RepoName;
+use crate::backend::privileged::pisi_args;
+use crate::backend::repo_probe::{probe_index, RepositoryUrl, SystemDistribution};
+use crate::backend::runner::PisiRunner;
+use crate::backend::xml_parser::XmlParser;
+use crate::config::settings::AppSettings;
 
-pub struct RepositoryManager;
+/// Ayarlar penceresindeki depo listesinde gösterilen bilgiler
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RepositoryInfo {
+    pub name: String,
+    pub url: String,
+    /// `pisi list-repo` sırası; küçük değer daha yüksek öncelik demektir
+    pub priority: usize,
+    pub enabled: bool,
+    /// İndeksin son güncellenme zamanı (UNIX saniyesi); indeks hiç indirilmemişse `None`
+    pub last_update: Option<u64>,
+    /// İndeksteki paket sayısı; indeks yoksa veya okunamazsa `None`
+    pub package_count: Option<usize>,
+    /// Yerel indeksin SHA1/imza doğrulama sonucu; indeks yoksa `None`
+    pub trust: Option<TrustStatus>,
+}
+
+/// Depo listesinde bir adım yukarı (daha yüksek öncelik) veya aşağı taşıma
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum MoveDirection {
+    Up,
+    Down,
+}
+
+pub struct RepositoryManager<'a> {
+    runner: &'a dyn PisiRunner,
+    index_dir: PathBuf,
+    verifier: IndexVerifier,
+}
 
-impl RepositoryManager {
-    pub fn add_repository(name: &str, url: &str) -> Result<()> {
-        let output = Command::new("pisi")
-        .args(["add-repo", name, url])
-        .output()?;
+impl<'a> RepositoryManager<'a> {
+    pub fn new(runner: &'a dyn PisiRunner) -> Self {
+        Self::from_settings(runner, &AppSettings::load())
+    }
 
-        if output.status.success() {
-            println!("Repository {} added successfully", name);
+    /// Uygulama ayarlarını diskten okumadan, verilen ayarlarla oluşturur
+    pub fn from_settings(runner: &'a dyn PisiRunner, settings: &AppSettings) -> Self {
+        Self {
+            runner,
+            index_dir: PathBuf::from(XmlParser::INDEX_DIR),
+            verifier: IndexVerifier::from_settings(settings),
+        }
+    }
+
+    /// İndeks dosyalarının aranacağı dizini değiştirir (varsayılan `/var/lib/pisi/index`)
+    pub fn with_index_dir(mut self, index_dir: impl Into<PathBuf>) -> Self {
+        self.index_dir = index_dir.into();
+        self
+    }
+
+    /// İndeks doğrulamasında kullanılacak ayarları değiştirir (varsayılan uygulama ayarları)
+    pub fn with_verifier(mut self, verifier: IndexVerifier) -> Self {
+        self.verifier = verifier;
+        self
+    }
+
+    /// Depoyu sona ekler. Önce adresteki indeks denetlenir; geçerli bir PiSi indeksi
+    /// değilse veya dağıtımı/mimarisi `system` ile uyuşmuyorsa pisi çağrılmaz.
+    pub async fn add_repository(&self, name: &RepoName, url: &str, system: &SystemDistribution) -> Result<(), PmError> {
+        Self::check_index(url, system).await?;
+        self.add(name, url, None, true).await
+    }
+
+    /// Depoyu verilen sıraya ekler (0 en yüksek öncelik); denetim `add_repository` ile aynıdır
+    pub async fn add_repository_at(
+        &self,
+        name: &RepoName,
+        url: &str,
+        position: usize,
+        system: &SystemDistribution,
+    ) -> Result<(), PmError> {
+        Self::check_index(url, system).await?;
+        self.add(name, url, Some(position), true).await
+    }
+
+    /// Adresteki indeksin başını okuyup bu sistemle uyumlu bir PiSi indeksi olduğunu doğrular
+    async fn check_index(url: &str, system: &SystemDistribution) -> Result<(), PmError> {
+        let probe = probe_index(url, system).await?;
+        if probe.mismatches.is_empty() {
+            return Ok(());
+        }
+        let mismatches: Vec<String> = probe
+            .mismatches
+            .iter()
+            .map(|m| format!("{} {} (expected {})", m.field, m.found, m.expected))
+            .collect();
+        Err(PmError::InvalidRequest {
+            message: format!("Repository {} does not match this system: {}", url, mismatches.join(", ")),
+        })
+    }
+
+    /// `fetch` verilmezse `--no-fetch` ile eklenir: pisi indeksi indirmez, ağa çıkmaz
+    async fn add(&self, name: &RepoName, url: &str, position: Option<usize>, fetch: bool) -> Result<(), PmError> {
+        RepositoryUrl::parse(url)?;
+        let position = position.map(|p| p.to_string());
+        let mut options = Vec::new();
+        if !fetch {
+            options.push("--no-fetch");
+        }
+        if let Some(position) = &position {
+            options.extend(["--at", position.as_str()]);
+        }
+        let output = self.runner.output(&pisi_args("add-repo", &options, &[name.as_str(), url])).await?;
+
+        if output.is_success() {
+            match position {
+                Some(position) => println!("Repository {} added at position {}", name, position),
+                None => println!("Repository {} added successfully", name),
+            }
             Ok(())
         } else {
-            let error = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow::anyhow!("Failed to add repository: {}", error))
+            Err(PmError::from_pisi_output(&output))
+        }
+    }
+
+    /// Depoyu bir adım yukarı veya aşağı taşır ve yeni listeyi döner.
+    /// pisi'de sıralama komutu olmadığı için depo kaldırılıp yeni sırasına yeniden eklenir;
+    /// ekleme başarısız olursa eski yerine geri konur.
+    pub async fn move_repository(&self, name: &RepoName, direction: MoveDirection) -> Result<Vec<RepositoryInfo>, PmError> {
+        let repos = self.list_repositories().await?;
+        let current = Self::find(&repos, name)?;
+
+        let target = match direction {
+            MoveDirection::Up => current.priority.checked_sub(1),
+            MoveDirection::Down => Some(current.priority + 1).filter(|&p| p < repos.len()),
+        };
+        let Some(target) = target else {
+            // Zaten en başta veya en sonda
+            return Ok(repos);
+        };
+
+        // Adres değişmediği için indeks indirilmez; taşıma ağ bağlantısına bağlı değildir
+        self.readd_repository(name, current, &current.url, target, false).await?;
+        self.list_repositories().await
+    }
+
+    /// Deponun adresini (örneğin daha hızlı bir yansı ile) değiştirir; sırası ve etkinliği korunur.
+    /// Eski kayıt kaldırılmadan önce yeni adresteki indeks denetlenir; geçerli bir PiSi
+    /// indeksi değilse veya dağıtımı/mimarisi `system` ile uyuşmuyorsa değişiklik yapılmaz.
+    pub async fn switch_mirror(&self, name: &RepoName, url: &str, system: &SystemDistribution) -> Result<Vec<RepositoryInfo>, PmError> {
+        RepositoryUrl::parse(url)?;
+        let repos = self.list_repositories().await?;
+        let current = Self::find(&repos, name)?;
+        if current.url == url {
+            return Ok(repos);
+        }
+
+        Self::check_index(url, system).await?;
+        self.readd_repository(name, current, url, current.priority, true).await?;
+        self.list_repositories().await
+    }
+
+    fn find<'r>(repos: &'r [RepositoryInfo], name: &RepoName) -> Result<&'r RepositoryInfo, PmError> {
+        repos
+            .iter()
+            .find(|r| r.name == name.as_str())
+            .ok_or_else(|| PmError::InvalidRequest { message: format!("Repository {} does not exist", name) })
+    }
+
+    /// Depoyu kaldırıp verilen adres ve sırayla yeniden ekler.
+    /// Ekleme başarısız olursa depo eski adresi ve sırasıyla `--no-fetch` ile (ağ
+    /// gerektirmeden) geri konur. `pisi remove-repo` indeksi de sildiği için indirme
+    /// yapılmayan eklemelerden sonra indeks ayrıca güncellenmeye çalışılır.
+    async fn readd_repository(
+        &self,
+        name: &RepoName,
+        current: &RepositoryInfo,
+        url: &str,
+        position: usize,
+        fetch: bool,
+    ) -> Result<(), PmError> {
+        self.remove_repository(name).await?;
+        if let Err(e) = self.add(name, url, Some(position), fetch).await {
+            println!("Failed to re-add repository {}, restoring it: {}", name, e);
+            if let Err(restore) = self.add(name, &current.url, Some(current.priority), false).await {
+                return Err(PmError::Internal {
+                    message: format!(
+                        "Repository {} was removed and could not be restored ({}); add it again with URL {}. Original error: {}",
+                        name, restore, current.url, e
+                    ),
+                });
+            }
+            self.finish_readd(name, current.enabled, false).await?;
+            return Err(e);
         }
+        self.finish_readd(name, current.enabled, fetch).await
     }
 
-    pub fn remove_repository(name: &str) -> Result<()> {
-        let output = Command::new("pisi")
-        .args(["remove-repo", name])
-        .output()?;
+    /// Yeniden eklenen depo etkin gelir; gerekirse indeksini indirip eski durumuna döndürür
+    async fn finish_readd(&self, name: &RepoName, enabled: bool, fetched: bool) -> Result<(), PmError> {
+        if !fetched {
+            if let Err(e) = self.update_repositories(Some(name)).await {
+                println!("Could not refresh the index of {}, run update-repo later: {}", name, e);
+            }
+        }
+        if !enabled {
+            self.disable_repository(name).await?;
+        }
+        Ok(())
+    }
 
-        if output.status.success() {
+    pub async fn remove_repository(&self, name: &RepoName) -> Result<(), PmError> {
+        let output = self.runner.output(&pisi_args("remove-repo", &[], &[name.as_str()])).await?;
+
+        if output.is_success() {
             println!("Repository {} removed successfully", name);
             Ok(())
         } else {
-            let error = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow::anyhow!("Failed to remove repository: {}", error))
+            Err(PmError::from_pisi_output(&output))
         }
     }
 
-    pub fn enable_repository(name: &str) -> Result<()> {
-        let output = Command::new("pisi")
-        .args(["enable-repo", name])
-        .output()?;
+    pub async fn enable_repository(&self, name: &RepoName) -> Result<(), PmError> {
+        let output = self.runner.output(&pisi_args("enable-repo", &[], &[name.as_str()])).await?;
 
-        if output.status.success() {
+        if output.is_success() {
             println!("Repository {} enabled successfully", name);
             Ok(())
         } else {
-            let error = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow::anyhow!("Failed to enable repository: {}", error))
+            Err(PmError::from_pisi_output(&output))
         }
     }
 
-    pub fn disable_repository(name: &str) -> Result<()> {
-        let output = Command::new("pisi")
-        .args(["disable-repo", name])
-        .output()?;
+    pub async fn disable_repository(&self, name: &RepoName) -> Result<(), PmError> {
+        let output = self.runner.output(&pisi_args("disable-repo", &[], &[name.as_str()])).await?;
 
-        if output.status.success() {
+        if output.is_success() {
             println!("Repository {} disabled successfully", name);
             Ok(())
         } else {
-            let error = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow::anyhow!("Failed to disable repository: {}", error))
+            Err(PmError::from_pisi_output(&output))
         }
     }
 
-    pub fn update_repositories() -> Result<()> {
-        let output = Command::new("pisi")
-        .args(["update-repo"])
-        .output()?;
+    /// Verilen depoyu, `name` yoksa tüm depoları günceller
+    pub async fn update_repositories(&self, name: Option<&RepoName>) -> Result<(), PmError> {
+        let operands: Vec<&str> = name.map(RepoName::as_str).into_iter().collect();
+        let output = self.runner.output(&pisi_args("update-repo", &[], &operands)).await?;
 
-        if output.status.success() {
+        if output.is_success() {
             println!("Repositories updated successfully");
             Ok(())
         } else {
-            let error = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow::anyhow!("Failed to update repositories: {}", error))
-        }
-    }
-
-    pub fn list_repositories() -> Result<Vec<(String, bool)>> {
-        let output = Command::new("pisi")
-        .args(["list-repo"])
-        .output()?;
-
-        if output.status.success() {
-            let output_str = String::from_utf8_lossy(&output.stdout);
-            let repos: Vec<(String, bool)> = output_str
-            .lines()
-            .skip(1) // Skip header
-            .filter_map(|line| {
-                let parts: Vec<&str> = line.split_whitespace().collect();
-                if parts.len() >= 2 {
-                    let name = parts[0].to_string();
-                    let enabled = parts[1] == "enabled";
-                    Some((name, enabled))
-                } else {
-                    None
-                }
-            })
-            .collect();
+            Err(PmError::from_pisi_output(&output))
+        }
+    }
+
+    /// Depoları öncelik sırasıyla döner; son güncelleme zamanı ve paket sayısı yerel indeksten okunur
+    pub async fn list_repositories(&self) -> Result<Vec<RepositoryInfo>, PmError> {
+        let output = self.runner.output(&["list-repo"]).await?;
+
+        if output.is_success() {
+            let mut repos = parse_list_repo(&output.stdout);
+            for repo in &mut repos {
+                self.read_index_info(repo);
+            }
             Ok(repos)
         } else {
-            let error = String::from_utf8_lossy(&output.stderr);
-            Err(anyhow::anyhow!("Failed to list repositories: {}", error))
+            Err(PmError::from_pisi_output(&output))
         }
     }
+
+    fn read_index_info(&self, repo: &mut RepositoryInfo) {
+        let Some(path) = XmlParser::find_index_file(&self.index_dir.join(&repo.name)) else {
+            return;
+        };
+
+        repo.last_update = fs::metadata(&path)
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+            .map(|d| d.as_secs());
+        repo.trust = Some(self.verifier.verify(&path));
+
+        match open_index(&path).and_then(count_packages) {
+            Ok(count) => repo.package_count = Some(count),
+            Err(e) => println!("Failed to count packages in {}: {}", path.display(), e),
+        }
+    }
+}
+
+/// `pisi list-repo` durum etiketleri. Çıktı `LC_ALL=C` ile alınsa da pisi'nin
+/// yerelleştirilmiş (Türkçe) çıktısı da tanınır.
+const ACTIVE_LABELS: &[&str] = &["active", "etkin", "aktif"];
+const INACTIVE_LABELS: &[&str] = &["inactive", "etkin değil", "pasif", "devre dışı"];
+
+/// `pisi list-repo` çıktısını ayrıştırır. pisi her depo için önce renkli
+/// `ad [active]` satırını, ardından girintili URL satırını yazar:
+///
+/// ```text
+/// pisi-2.0 [active]
+///    https://ciftlik.pisilinux.org/2.0/pisi-index.xml.xz
+/// ```
+///
+/// ANSI renk kodları atılır; tanınmayan satırlar (uyarılar vb.) yok sayılır.
+pub fn parse_list_repo(stdout: &str) -> Vec<RepositoryInfo> {
+    let mut repos: Vec<RepositoryInfo> = Vec::new();
+    let mut awaiting_url = false;
+
+    for line in stdout.lines().map(strip_ansi) {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+
+        if let Some((name, enabled)) = parse_repo_header(line) {
+            repos.push(RepositoryInfo {
+                name: name.to_string(),
+                url: String::new(),
+                priority: repos.len(),
+                enabled,
+                last_update: None,
+                package_count: None,
+                trust: None,
+            });
+            awaiting_url = true;
+        } else if awaiting_url && !line.contains(char::is_whitespace) {
+            if let Some(repo) = repos.last_mut() {
+                repo.url = line.to_string();
+            }
+            awaiting_url = false;
+        }
+    }
+
+    repos
+}
+
+/// `ad [durum]` satırından depo adını ve etkinliğini çıkarır
+fn parse_repo_header(line: &str) -> Option<(&str, bool)> {
+    let (name, status) = line.strip_suffix(']')?.rsplit_once('[')?;
+    let name = name.trim();
+    if name.is_empty() || name.contains(char::is_whitespace) {
+        return None;
+    }
+
+    let status = status.trim().to_lowercase();
+    if INACTIVE_LABELS.contains(&status.as_str()) {
+        Some((name, false))
+    } else if ACTIVE_LABELS.contains(&status.as_str()) {
+        Some((name, true))
+    } else {
+        None
+    }
+}
+
+/// ANSI kaçış dizilerini (`ESC [ ... harf`) siler
+fn strip_ansi(line: &str) -> String {
+    let mut result = String::with_capacity(line.len());
+    let mut chars = line.chars();
+
+    while let Some(c) = chars.next() {
+        if c != '\x1b' {
+            result.push(c);
+            continue;
+        }
+        if chars.next() == Some('[') {
+            for c in chars.by_ref() {
+                if c.is_ascii_alphabetic() {
+                    break;
+                }
+            }
+        }
+    }
+
+    result
 }
@@ -0,0 +1,288 @@
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet};
+use crate::backend::error::PmError;
+use crate::backend::installed_db::InstalledPackage;
+use crate::backend::names::PackageName;
+use crate::backend::version::PackageVersion;
+use crate::backend::xml_parser::{Dependency, PackageInfo};
+
+/// Bir paketin plana neden girdiği
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PlanReason {
+    /// Kullanıcı doğrudan istedi
+    Requested,
+    /// Listelenen paketlerin bağımlılığı olarak çekildi
+    Dependency { required_by: String },
+    /// Kurulacak paketle çakıştığı için kaldırılacak
+    Conflict { with: String },
+    /// Yeni paket bunun yerini alıyor
+    Replaced { by: String },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlannedPackage {
+    pub name: String,
+    pub version: String,
+    pub release: u32,
+    /// Yükseltmelerde kurulu olan sürüm
+    pub installed_version: Option<String>,
+    pub repository: String,
+    pub package_size: u64,
+    pub installed_size: u64,
+    pub reason: PlanReason,
+}
+
+/// Kullanıcı onayından önce gösterilecek işlem planı.
+/// `install` ve `upgrade` listeleri bağımlılıklar önce gelecek şekilde sıralıdır.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionPlan {
+    pub install: Vec<PlannedPackage>,
+    pub upgrade: Vec<PlannedPackage>,
+    pub remove: Vec<PlannedPackage>,
+    /// İndirilecek toplam paket boyutu
+    pub download_size: u64,
+    /// Kurulacak/yükseltilecek paketlerin diskte kaplayacağı toplam boyut
+    pub installed_size: u64,
+    /// Kaldırılan paketlerden ve eski sürümlerden boşalacak boyut
+    pub freed_size: u64,
+}
+
+impl TransactionPlan {
+    pub fn is_empty(&self) -> bool {
+        self.install.is_empty() && self.upgrade.is_empty() && self.remove.is_empty()
+    }
+}
+
+/// İndeks ve kurulu paketler üzerinde çalışan bağımlılık çözücü
+pub struct Resolver<'a> {
+    index: HashMap<&'a str, &'a PackageInfo>,
+    installed: HashMap<&'a str, &'a InstalledPackage>,
+}
+
+impl<'a> Resolver<'a> {
+    pub fn new(index: &'a [PackageInfo], installed: &'a [InstalledPackage]) -> Self {
+        Self {
+            index: index.iter().map(|p| (p.name.as_str(), p)).collect(),
+            installed: installed.iter().map(|p| (p.name.as_str(), p)).collect(),
+        }
+    }
+
+    /// İndeksteki sürüm kurulu sürümden yeni mi?
+    pub fn is_upgradable(candidate: &PackageInfo, installed: &InstalledPackage) -> bool {
+        match (candidate.package_version(), PackageVersion::new(&installed.version, installed.release)) {
+            (Some(new), Ok(old)) => new > old,
+            _ => candidate.release > installed.release,
+        }
+    }
+
+    /// Verilen paketleri kurmak için gereken planı çıkarır
+    /// İstenen paket indekste yoksa `PackageNotFound`, bağımlılıklar karşılanamıyorsa `DependencyConflict` döner
+    pub fn plan_install(&self, names: &[PackageName]) -> Result<TransactionPlan, PmError> {
+        let mut state = PlanState::default();
+
+        for name in names {
+            let package = self.index.get(name.as_str())
+                .ok_or_else(|| PmError::PackageNotFound { message: format!("Package {} not found in any repository", name) })?;
+
+            match self.installed.get(name.as_str()) {
+                Some(installed) if !Self::is_upgradable(package, installed) => continue,
+                _ => self.visit(package, PlanReason::Requested, &mut state)?,
+            }
+        }
+
+        self.add_removals(&mut state)?;
+        self.check_installed_constraints(&state)?;
+        Ok(self.build_plan(state))
+    }
+
+    /// Paketi ve (önce) eksik bağımlılıklarını plana ekler (post-order DFS)
+    fn visit(&self, package: &'a PackageInfo, reason: PlanReason, state: &mut PlanState<'a>) -> Result<(), PmError> {
+        // Döngüsel bağımlılıklarda ikinci ziyaret sessizce atlanır
+        if !state.visited.insert(package.name.as_str()) {
+            return Ok(());
+        }
+
+        for dep in &package.dependencies {
+            if let Some(candidate) = self.needs_action(dep, &package.name)? {
+                self.visit(candidate, PlanReason::Dependency { required_by: package.name.clone() }, state)?;
+            }
+        }
+
+        state.ordered.push((package, reason));
+        Ok(())
+    }
+
+    /// Bağımlılık kurulu sürümle karşılanıyorsa `None`, aksi halde kurulacak/yükseltilecek aday
+    fn needs_action(&self, dep: &Dependency, required_by: &str) -> Result<Option<&'a PackageInfo>, PmError> {
+        if let Some(installed) = self.installed.get(dep.name.as_str()) {
+            if dep.is_satisfied_by(&installed.version, installed.release) {
+                return Ok(None);
+            }
+        }
+
+        let candidate = self.index.get(dep.name.as_str()).ok_or_else(|| {
+            dependency_conflict(format!("{} requires {}, which is not available in any repository", required_by, dep.name))
+        })?;
+
+        if !dep.is_satisfied_by(&candidate.version, candidate.release) {
+            return Err(dependency_conflict(format!(
+                "{} requires {} {}, but only {}-{} is available",
+                required_by, dep.name, dep.constraint, candidate.version, candidate.release
+            )));
+        }
+
+        Ok(Some(candidate))
+    }
+
+    /// Çakışan ve yerine geçilen kurulu paketleri kaldırma listesine ekler
+    fn add_removals(&self, state: &mut PlanState<'a>) -> Result<(), PmError> {
+        let planned: HashSet<&str> = state.ordered.iter().map(|(p, _)| p.name.as_str()).collect();
+
+        for (package, _) in &state.ordered {
+            for conflict in &package.conflicts {
+                if planned.contains(conflict.name.as_str()) {
+                    let other = self.index[conflict.name.as_str()];
+                    if conflict.is_satisfied_by(&other.version, other.release) {
+                        return Err(dependency_conflict(format!(
+                            "{} conflicts with {}, both are part of this transaction",
+                            package.name, conflict.name
+                        )));
+                    }
+                }
+                if let Some(installed) = self.installed.get(conflict.name.as_str()) {
+                    if conflict.is_satisfied_by(&installed.version, installed.release) {
+                        state.removals.push((installed, PlanReason::Conflict { with: package.name.clone() }));
+                    }
+                }
+            }
+
+            for replaced in &package.replaces {
+                if let Some(installed) = self.installed.get(replaced.name.as_str()) {
+                    if replaced.name != package.name && replaced.is_satisfied_by(&installed.version, installed.release) {
+                        state.removals.push((installed, PlanReason::Replaced { by: package.name.clone() }));
+                    }
+                }
+            }
+        }
+
+        // Kurulu paketlerin kendi <Conflicts> listeleri: pisi bunları da kaldırır.
+        // Plan içinde yükseltilen paketlerin yeni sürümlerinin listesi yukarıda denetlendi.
+        let mut installed: Vec<&&InstalledPackage> = self.installed.values().collect();
+        installed.sort_by(|a, b| a.name.cmp(&b.name));
+        for installed in installed {
+            if planned.contains(installed.name.as_str()) {
+                continue;
+            }
+            for conflict in &installed.conflicts {
+                let Some((package, _)) = state.ordered.iter().find(|(p, _)| p.name == conflict.name) else {
+                    continue;
+                };
+                if conflict.is_satisfied_by(&package.version, package.release) {
+                    state.removals.push((installed, PlanReason::Conflict { with: package.name.clone() }));
+                }
+            }
+        }
+
+        let mut seen = HashSet::new();
+        state.removals.retain(|(p, _)| seen.insert(p.name.as_str()));
+        Ok(())
+    }
+
+    /// Yükseltilen paketlerin yeni sürümü, plan dışında kalan kurulu paketlerin
+    /// bu paketler üzerindeki sürüm kısıtlarını karşılamalı
+    fn check_installed_constraints(&self, state: &PlanState<'a>) -> Result<(), PmError> {
+        let planned: HashSet<&str> = state.ordered.iter().map(|(p, _)| p.name.as_str()).collect();
+        let removed: HashSet<&str> = state.removals.iter().map(|(p, _)| p.name.as_str()).collect();
+
+        let mut dependents: Vec<&&InstalledPackage> = self
+            .installed
+            .values()
+            .filter(|p| !planned.contains(p.name.as_str()) && !removed.contains(p.name.as_str()))
+            .collect();
+        dependents.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for (package, _) in &state.ordered {
+            let Some(old) = self.installed.get(package.name.as_str()) else {
+                continue;
+            };
+            for dependent in &dependents {
+                let broken = dependent
+                    .dependencies
+                    .iter()
+                    .find(|d| d.name == package.name && !d.is_satisfied_by(&package.version, package.release));
+                if let Some(dep) = broken {
+                    return Err(dependency_conflict(format!(
+                        "Upgrading {} from {}-{} to {}-{} breaks installed package {}, which requires {} {}",
+                        package.name, old.version, old.release, package.version, package.release,
+                        dependent.name, dep.name, dep.constraint
+                    )));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Kurulu paketin indeks karşılığı (varsa)
+    pub fn candidate(&self, name: &str) -> Option<&'a PackageInfo> {
+        self.index.get(name).copied()
+    }
+
+    pub fn installed(&self, name: &str) -> Option<&'a InstalledPackage> {
+        self.installed.get(name).copied()
+    }
+
+    fn build_plan(&self, state: PlanState<'a>) -> TransactionPlan {
+        let mut plan = TransactionPlan::default();
+
+        for (package, reason) in state.ordered {
+            let installed = self.installed.get(package.name.as_str());
+            let planned = PlannedPackage {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                release: package.release,
+                installed_version: installed.map(|i| format!("{}-{}", i.version, i.release)),
+                repository: package.repository.clone(),
+                package_size: package.package_size,
+                installed_size: package.installed_size,
+                reason,
+            };
+            plan.download_size += planned.package_size;
+            plan.installed_size += planned.installed_size;
+
+            match installed {
+                Some(old) => {
+                    plan.freed_size += old.installed_size;
+                    plan.upgrade.push(planned);
+                }
+                None => plan.install.push(planned),
+            }
+        }
+
+        for (installed, reason) in state.removals {
+            plan.freed_size += installed.installed_size;
+            plan.remove.push(PlannedPackage {
+                name: installed.name.clone(),
+                version: installed.version.clone(),
+                release: installed.release,
+                installed_version: Some(format!("{}-{}", installed.version, installed.release)),
+                repository: String::new(),
+                package_size: 0,
+                installed_size: installed.installed_size,
+                reason,
+            });
+        }
+
+        plan
+    }
+}
+
+#[derive(Default)]
+struct PlanState<'a> {
+    visited: HashSet<&'a str>,
+    ordered: Vec<(&'a PackageInfo, PlanReason)>,
+    removals: Vec<(&'a InstalledPackage, PlanReason)>,
+}
+
+fn dependency_conflict(message: String) -> PmError {
+    PmError::DependencyConflict { message }
+}
@@ -0,0 +1,102 @@
+use serde::{Serialize, Deserialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use crate::backend::installed_db::InstalledPackage;
+use crate::backend::xml_parser::PackageInfo;
+
+/// Bir paketi gerektiren paket
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReverseDependency {
+    pub name: String,
+    /// Bu paketi doğrudan gerektiren paket (özyinelemeli sorguda zincirdeki bir önceki halka)
+    pub via: String,
+    /// 1: doğrudan bağımlı, 2: bağımlının bağımlısı, ...
+    pub depth: usize,
+    pub installed: bool,
+}
+
+/// "Bu paketi kim gerektiriyor?" sorguları için ters bağımlılık haritası
+#[derive(Debug, Clone, Default)]
+pub struct ReverseDependencyMap {
+    dependents: HashMap<String, Vec<String>>,
+    installed: HashSet<String>,
+}
+
+impl ReverseDependencyMap {
+    /// Sadece kurulu paketlerin (kurulu sürümlerinin) bağımlılıklarından harita kurar
+    pub fn from_installed(installed: &[InstalledPackage]) -> Self {
+        let mut map = Self {
+            installed: installed.iter().map(|p| p.name.clone()).collect(),
+            ..Default::default()
+        };
+        for package in installed {
+            map.add(&package.name, package.dependencies.iter().map(|d| d.name.as_str()));
+        }
+        map.finish()
+    }
+
+    /// İndeksteki tüm paketlerden harita kurar. Kurulu olup indekste bulunmayan
+    /// paketler de (ör. depodan kaldırılmış olanlar) kurulu bağımlılıklarıyla eklenir.
+    pub fn from_index(index: &[PackageInfo], installed: &[InstalledPackage]) -> Self {
+        let mut map = Self {
+            installed: installed.iter().map(|p| p.name.clone()).collect(),
+            ..Default::default()
+        };
+        let in_index: HashSet<&str> = index.iter().map(|p| p.name.as_str()).collect();
+
+        for package in index {
+            map.add(&package.name, package.dependencies.iter().map(|d| d.name.as_str()));
+        }
+        for package in installed.iter().filter(|p| !in_index.contains(p.name.as_str())) {
+            map.add(&package.name, package.dependencies.iter().map(|d| d.name.as_str()));
+        }
+        map.finish()
+    }
+
+    fn add<'d>(&mut self, name: &str, dependencies: impl Iterator<Item = &'d str>) {
+        for dep in dependencies {
+            self.dependents.entry(dep.to_string()).or_default().push(name.to_string());
+        }
+    }
+
+    fn finish(mut self) -> Self {
+        for names in self.dependents.values_mut() {
+            names.sort();
+            names.dedup();
+        }
+        self
+    }
+
+    /// Paketi doğrudan gerektiren paketlerin isimleri
+    pub fn direct(&self, name: &str) -> &[String] {
+        self.dependents.get(name).map(|v| v.as_slice()).unwrap_or(&[])
+    }
+
+    /// Ters bağımlılıkları döner. `recursive` ise bağımlıların bağımlıları da
+    /// (genişlik öncelikli sırayla) eklenir; `installed_only` ise sadece kurulu paketler.
+    pub fn query(&self, name: &str, installed_only: bool, recursive: bool) -> Vec<ReverseDependency> {
+        let mut result = Vec::new();
+        let mut seen: HashSet<&str> = HashSet::from([name]);
+        let mut queue = VecDeque::from([(name, 0usize)]);
+
+        while let Some((current, depth)) = queue.pop_front() {
+            for dependent in self.direct(current) {
+                let installed = self.installed.contains(dependent);
+                if (installed_only && !installed) || !seen.insert(dependent.as_str()) {
+                    continue;
+                }
+
+                result.push(ReverseDependency {
+                    name: dependent.clone(),
+                    via: current.to_string(),
+                    depth: depth + 1,
+                    installed,
+                });
+                if recursive {
+                    queue.push_back((dependent.as_str(), depth + 1));
+                }
+            }
+        }
+
+        result
+    }
+}
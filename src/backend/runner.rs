@@ -0,0 +1,227 @@
+use async_trait::async_trait;
+use std::collections::{HashMap, VecDeque};
+use std::ffi::OsString;
+use std::io;
+use std::path::PathBuf;
+use std::process::Stdio;
+use std::sync::Mutex;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio::process::Command;
+use crate::backend::privileged;
+
+/// Tamamlanmış bir pisi çağrısının çıktısı
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PisiOutput {
+    /// Sinyalle sonlanan süreçlerde -1
+    pub exit_code: i32,
+    pub stdout: String,
+    pub stderr: String,
+}
+
+impl PisiOutput {
+    pub fn success(stdout: impl Into<String>) -> Self {
+        Self { exit_code: 0, stdout: stdout.into(), stderr: String::new() }
+    }
+
+    pub fn failure(exit_code: i32, stderr: impl Into<String>) -> Self {
+        Self { exit_code, stdout: String::new(), stderr: stderr.into() }
+    }
+
+    pub fn is_success(&self) -> bool {
+        self.exit_code == 0
+    }
+}
+
+/// pisi çağrılarını soyutlar. Uygulamada `SystemPisiRunner`, testlerde `ScriptedPisiRunner`
+/// kullanılır; Tauri komutlarına `Box<dyn PisiRunner>` olarak managed state ile verilir.
+#[async_trait]
+pub trait PisiRunner: Send + Sync {
+    /// pisi'yi çalıştırır ve bitmesini bekleyip çıktısını döner
+    async fn output(&self, args: &[&str]) -> io::Result<PisiOutput>;
+
+    /// pisi'yi çalıştırır; stdout satırlarını (`\n` ve `\r` ile bölünmüş) geldikçe `on_line`'a verir.
+    /// `on_spawn` süreç kimliğini alır (iptal için). Dönen çıktının `stdout` alanı boştur.
+    async fn stream(
+        &self,
+        args: &[&str],
+        on_spawn: &mut (dyn FnMut(u32) + Send),
+        on_line: &mut (dyn for<'l> FnMut(&'l str) + Send),
+    ) -> io::Result<PisiOutput>;
+}
+
+/// Gerçek pisi'yi alt süreç olarak çalıştırır. Sistemi değiştiren komutlar
+/// pkexec ve yardımcı program üzerinden, sorgular doğrudan çalıştırılır.
+#[derive(Debug, Default)]
+pub struct SystemPisiRunner {
+    envs: Vec<(OsString, OsString)>,
+}
+
+impl SystemPisiRunner {
+    /// Başlatılan süreçlerin ortamına değişken ekler (ör. testlerde sahte `pkexec` için `PATH`).
+    /// `privileged::HELPER_PATH_ENV` verilirse yardımcı program da bu yoldan çalıştırılır.
+    pub fn with_env(mut self, key: impl Into<OsString>, value: impl Into<OsString>) -> Self {
+        self.envs.push((key.into(), value.into()));
+        self
+    }
+
+    fn helper_path(&self) -> PathBuf {
+        self.envs
+            .iter()
+            .rfind(|(key, _)| key == privileged::HELPER_PATH_ENV)
+            .map(|(_, value)| PathBuf::from(value))
+            .unwrap_or_else(privileged::helper_path)
+    }
+
+    fn command(&self, args: &[&str]) -> Command {
+        let requires_root = args
+            .first()
+            .is_some_and(|verb| privileged::ALLOWED_VERBS.contains(verb));
+        let mut command = if requires_root {
+            Command::from(privileged::helper_command(&self.helper_path(), args))
+        } else {
+            let mut command = Command::new("pisi");
+            command.args(args);
+            command
+        };
+        command.envs(self.envs.iter().map(|(key, value)| (key, value)));
+        // Çıktı ayrıştırması İngilizce mesajlara göre yapılır
+        command.env("LC_ALL", "C").stdin(Stdio::null());
+        command
+    }
+}
+
+#[async_trait]
+impl PisiRunner for SystemPisiRunner {
+    async fn output(&self, args: &[&str]) -> io::Result<PisiOutput> {
+        let output = self.command(args).output().await?;
+        Ok(PisiOutput {
+            exit_code: output.status.code().unwrap_or(-1),
+            stdout: String::from_utf8_lossy(&output.stdout).to_string(),
+            stderr: String::from_utf8_lossy(&output.stderr).to_string(),
+        })
+    }
+
+    async fn stream(
+        &self,
+        args: &[&str],
+        on_spawn: &mut (dyn FnMut(u32) + Send),
+        on_line: &mut (dyn for<'l> FnMut(&'l str) + Send),
+    ) -> io::Result<PisiOutput> {
+        let mut child = self.command(args)
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()?;
+        on_spawn(child.id().unwrap_or_default());
+
+        let stdout = child.stdout.take().ok_or_else(|| io::Error::other("Failed to capture pisi stdout"))?;
+        let mut stderr = child.stderr.take().ok_or_else(|| io::Error::other("Failed to capture pisi stderr"))?;
+
+        // stderr ayrı bir görevde toplanır ki pisi yazarken tıkanmasın
+        let stderr_task = tokio::spawn(async move {
+            let mut buf = Vec::new();
+            let _ = stderr.read_to_end(&mut buf).await;
+            String::from_utf8_lossy(&buf).to_string()
+        });
+
+        let read_result = read_lines(stdout, on_line).await;
+        let status = child.wait().await;
+        let stderr = stderr_task.await.unwrap_or_default();
+
+        read_result?;
+        Ok(PisiOutput {
+            exit_code: status?.code().unwrap_or(-1),
+            stdout: String::new(),
+            stderr,
+        })
+    }
+}
+
+/// Okuyucudan gelen veriyi `\n` ve `\r` ile bölerek satır satır işler
+/// (pisi indirme ilerlemesini aynı satırı `\r` ile yeniden yazarak gösterir)
+async fn read_lines(mut reader: impl AsyncRead + Unpin, on_line: &mut (dyn for<'l> FnMut(&'l str) + Send)) -> io::Result<()> {
+    let mut pending = Vec::new();
+    let mut chunk = [0u8; 4096];
+
+    loop {
+        let n = reader.read(&mut chunk).await?;
+        if n == 0 {
+            break;
+        }
+        for &byte in &chunk[..n] {
+            if byte == b'\n' || byte == b'\r' {
+                on_line(&String::from_utf8_lossy(&pending));
+                pending.clear();
+            } else {
+                pending.push(byte);
+            }
+        }
+    }
+    if !pending.is_empty() {
+        on_line(&String::from_utf8_lossy(&pending));
+    }
+    Ok(())
+}
+
+/// Kaydedilmiş çıktıları tekrar oynatan sahte pisi. Her argüman listesi için
+/// sırayla dönülecek çıktılar tanımlanır; tanımsız bir çağrı hata döner.
+#[derive(Debug, Default)]
+pub struct ScriptedPisiRunner {
+    responses: Mutex<HashMap<Vec<String>, VecDeque<PisiOutput>>>,
+    calls: Mutex<Vec<Vec<String>>>,
+}
+
+impl ScriptedPisiRunner {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// `args` ile yapılan bir sonraki çağrıya `output` ile cevap verir
+    pub fn expect(self, args: &[&str], output: PisiOutput) -> Self {
+        self.responses
+            .lock()
+            .unwrap()
+            .entry(Self::key(args))
+            .or_default()
+            .push_back(output);
+        self
+    }
+
+    /// Şimdiye kadar yapılan çağrılar, sırasıyla
+    pub fn calls(&self) -> Vec<Vec<String>> {
+        self.calls.lock().unwrap().clone()
+    }
+
+    fn key(args: &[&str]) -> Vec<String> {
+        args.iter().map(|s| s.to_string()).collect()
+    }
+
+    fn next(&self, args: &[&str]) -> io::Result<PisiOutput> {
+        let key = Self::key(args);
+        self.calls.lock().unwrap().push(key.clone());
+        self.responses
+            .lock()
+            .unwrap()
+            .get_mut(&key)
+            .and_then(|queue| queue.pop_front())
+            .ok_or_else(|| io::Error::other(format!("Unexpected pisi call: {}", key.join(" "))))
+    }
+}
+
+#[async_trait]
+impl PisiRunner for ScriptedPisiRunner {
+    async fn output(&self, args: &[&str]) -> io::Result<PisiOutput> {
+        self.next(args)
+    }
+
+    async fn stream(
+        &self,
+        args: &[&str],
+        _on_spawn: &mut (dyn FnMut(u32) + Send),
+        on_line: &mut (dyn for<'l> FnMut(&'l str) + Send),
+    ) -> io::Result<PisiOutput> {
+        // Sahte süreçlerin gerçek bir pid'i yok; işlem kayıtlı kalır ama sinyal gönderilmez
+        let output = self.next(args)?;
+        read_lines(output.stdout.as_bytes(), on_line).await?;
+        Ok(PisiOutput { stdout: String::new(), ..output })
+    }
+}
@@ -0,0 +1,176 @@
+use serde::{Serialize, Deserialize};
+use std::collections::HashSet;
+use crate::backend::names::PackageName;
+use crate::backend::error::PmError;
+use crate::backend::operation::{run_pisi_with_progress, OperationProgress, OperationRegistry};
+use crate::backend::privileged::pisi_args;
+use crate::backend::runner::PisiRunner;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum TransactionActionKind {
+    Install,
+    Remove,
+    Upgrade,
+}
+
+impl TransactionActionKind {
+    /// Çalıştırma sırası: önce kaldırma (çakışan paketler yer açsın), sonra kurulum ve yükseltme
+    const ORDER: [Self; 3] = [Self::Remove, Self::Install, Self::Upgrade];
+
+    fn pisi_command(self) -> &'static str {
+        match self {
+            Self::Install => "it",
+            Self::Remove => "rm",
+            Self::Upgrade => "up",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionAction {
+    pub kind: TransactionActionKind,
+    pub package: PackageName,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PackageResultStatus {
+    Succeeded,
+    Failed,
+    /// Hiç denenmedi: işlem iptal edildi ya da sabitli bir paketi değiştirecekti
+    Skipped,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PackageResult {
+    pub package: String,
+    pub kind: TransactionActionKind,
+    pub status: PackageResultStatus,
+    /// Başarısızlık veya atlanma nedeni
+    pub error: Option<PmError>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionResult {
+    pub operation_id: String,
+    pub results: Vec<PackageResult>,
+    pub cancelled: bool,
+}
+
+impl TransactionResult {
+    pub fn is_success(&self) -> bool {
+        self.results.iter().all(|r| r.status == PackageResultStatus::Succeeded)
+    }
+}
+
+/// Aynı paketin birden fazla işlemde geçmediğini ve listenin boş olmadığını doğrular
+pub fn validate_actions(actions: &[TransactionAction]) -> Result<(), PmError> {
+    if actions.is_empty() {
+        return Err(PmError::InvalidRequest { message: "Transaction contains no actions".to_string() });
+    }
+
+    let mut seen = HashSet::new();
+    for action in actions {
+        if !seen.insert(action.package.as_str()) {
+            return Err(PmError::InvalidRequest {
+                message: format!("Package {} appears more than once in the transaction", action.package),
+            });
+        }
+    }
+    Ok(())
+}
+
+/// İşlemleri türlerine göre gruplayıp her tür için tek bir pisi çağrısı yapar.
+/// Bir grup başarısız olursa hangi paketin sorunlu olduğunu bulmak için
+/// o gruptaki paketler tek tek yeniden denenir. İşlem baştan sona `registry` içinde
+/// kayıtlı kalır; iptal edildiğinde (iki pisi çağrısı arasında da) kalan işlemler atlanır.
+pub async fn run_actions(
+    runner: &dyn PisiRunner,
+    registry: &OperationRegistry,
+    operation_id: &str,
+    actions: &[TransactionAction],
+    mut on_progress: impl FnMut(OperationProgress) + Send,
+) -> TransactionResult {
+    let mut results = Vec::new();
+    let mut cancelled = false;
+    registry.begin(operation_id, &format!("transaction of {} packages", actions.len()));
+
+    for kind in TransactionActionKind::ORDER {
+        let packages: Vec<&str> = actions
+            .iter()
+            .filter(|a| a.kind == kind)
+            .map(|a| a.package.as_str())
+            .collect();
+        if packages.is_empty() {
+            continue;
+        }
+
+        if cancelled {
+            results.extend(packages.iter().map(|p| skipped(p, kind)));
+            continue;
+        }
+
+        match run_group(runner, registry, operation_id, kind, &packages, &mut on_progress).await {
+            Ok(()) => results.extend(packages.iter().map(|p| result(p, kind, Ok(())))),
+            Err(PmError::Cancelled) => {
+                cancelled = true;
+                results.extend(packages.iter().map(|p| skipped(p, kind)));
+            }
+            Err(error) if packages.len() == 1 => {
+                // Tek paketlik grupta yeniden denemenin anlamı yok
+                results.push(result(packages[0], kind, Err(error)));
+            }
+            Err(_) => {
+                println!("Batch {} failed, retrying packages one by one", kind.pisi_command());
+                for package in &packages {
+                    if cancelled {
+                        results.push(skipped(package, kind));
+                        continue;
+                    }
+                    let outcome = run_group(runner, registry, operation_id, kind, &[package], &mut on_progress).await;
+                    if matches!(outcome, Err(PmError::Cancelled)) {
+                        cancelled = true;
+                        results.push(skipped(package, kind));
+                    } else {
+                        results.push(result(package, kind, outcome));
+                    }
+                }
+            }
+        }
+    }
+
+    registry.finish(operation_id);
+    TransactionResult {
+        operation_id: operation_id.to_string(),
+        results,
+        cancelled,
+    }
+}
+
+async fn run_group(
+    runner: &dyn PisiRunner,
+    registry: &OperationRegistry,
+    operation_id: &str,
+    kind: TransactionActionKind,
+    packages: &[&str],
+    on_progress: &mut (impl FnMut(OperationProgress) + Send),
+) -> Result<(), PmError> {
+    let args = pisi_args(kind.pisi_command(), &["-y"], packages);
+    run_pisi_with_progress(runner, registry, operation_id, &args, on_progress).await
+}
+
+fn result(package: &str, kind: TransactionActionKind, outcome: Result<(), PmError>) -> PackageResult {
+    let (status, error) = match outcome {
+        Ok(()) => (PackageResultStatus::Succeeded, None),
+        Err(error) => (PackageResultStatus::Failed, Some(error)),
+    };
+    PackageResult { package: package.to_string(), kind, status, error }
+}
+
+fn skipped(package: &str, kind: TransactionActionKind) -> PackageResult {
+    PackageResult {
+        package: package.to_string(),
+        kind,
+        status: PackageResultStatus::Skipped,
+        error: Some(PmError::Cancelled),
+    }
+}
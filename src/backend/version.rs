@@ -0,0 +1,192 @@
+use anyhow::Result;
+use serde::{Serialize, Deserialize};
+use std::cmp::Ordering;
+use std::fmt;
+use std::str::FromStr;
+
+/// PiSi sürüm dizgesi (ör. `2.30`, `1.0a`, `5.2_rc1`, `9.1_p2`).
+///
+/// Sıralama kuralları:
+/// - Nokta ile ayrılan parçalar sayısal karşılaştırılır (`1.10 > 1.9`),
+///   parçanın sonundaki harfler ayrıca karşılaştırılır (`1.0 < 1.0a < 1.0b`).
+/// - Ortak önek eşitse parçası fazla olan büyüktür (`1.0 < 1.0.0`).
+/// - `_` ile gelen ekler: `_alpha < _beta < _pre < _rc < (ek yok) < _p`,
+///   ekin arkasındaki sayı da karşılaştırılır (`_rc1 < _rc2`).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Version {
+    raw: String,
+    parts: Vec<VersionPart>,
+    suffixes: Vec<VersionSuffix>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct VersionPart {
+    number: u64,
+    letters: String,
+}
+
+/// Ek anahtar kelimeleri, sıralamadaki konumlarıyla birlikte
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum SuffixKind {
+    Alpha,
+    Beta,
+    Pre,
+    Rc,
+    /// Ek olmaması; eksik ekler bununla karşılaştırılır
+    None,
+    P,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+struct VersionSuffix {
+    kind: SuffixKind,
+    number: u64,
+}
+
+impl VersionSuffix {
+    const NONE: Self = Self { kind: SuffixKind::None, number: 0 };
+
+    fn parse(s: &str) -> Result<Self> {
+        const KEYWORDS: [(&str, SuffixKind); 5] = [
+            ("alpha", SuffixKind::Alpha),
+            ("beta", SuffixKind::Beta),
+            ("pre", SuffixKind::Pre),
+            ("rc", SuffixKind::Rc),
+            ("p", SuffixKind::P),
+        ];
+
+        for (keyword, kind) in KEYWORDS {
+            if let Some(rest) = s.strip_prefix(keyword) {
+                let number = if rest.is_empty() {
+                    0
+                } else {
+                    rest.parse().map_err(|_| anyhow::anyhow!("Invalid version suffix: _{}", s))?
+                };
+                return Ok(Self { kind, number });
+            }
+        }
+        Err(anyhow::anyhow!("Unknown version suffix: _{}", s))
+    }
+}
+
+impl Version {
+    pub fn parse(s: &str) -> Result<Self> {
+        let raw = s.trim();
+        if raw.is_empty() {
+            return Err(anyhow::anyhow!("Empty version string"));
+        }
+
+        let mut sections = raw.split('_');
+        let base = sections.next().unwrap_or_default();
+
+        let parts = base
+            .split('.')
+            .map(|part| {
+                let digits_end = part.find(|c: char| !c.is_ascii_digit()).unwrap_or(part.len());
+                let (digits, letters) = part.split_at(digits_end);
+                if digits.is_empty() || !letters.chars().all(|c| c.is_ascii_alphabetic()) {
+                    return Err(anyhow::anyhow!("Invalid version: {}", raw));
+                }
+                Ok(VersionPart {
+                    number: digits.parse().map_err(|_| anyhow::anyhow!("Invalid version: {}", raw))?,
+                    letters: letters.to_string(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let suffixes = sections.map(VersionSuffix::parse).collect::<Result<Vec<_>>>()?;
+
+        Ok(Self { raw: raw.to_string(), parts, suffixes })
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+}
+
+impl FromStr for Version {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s)
+    }
+}
+
+impl TryFrom<String> for Version {
+    type Error = anyhow::Error;
+
+    fn try_from(s: String) -> Result<Self> {
+        Self::parse(&s)
+    }
+}
+
+impl From<Version> for String {
+    fn from(v: Version) -> Self {
+        v.raw
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl Ord for Version {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.parts.cmp(&other.parts).then_with(|| {
+            let len = self.suffixes.len().max(other.suffixes.len());
+            (0..len)
+                .map(|i| {
+                    let a = self.suffixes.get(i).unwrap_or(&VersionSuffix::NONE);
+                    let b = other.suffixes.get(i).unwrap_or(&VersionSuffix::NONE);
+                    a.cmp(b)
+                })
+                .find(|ord| *ord != Ordering::Equal)
+                .unwrap_or(Ordering::Equal)
+        })
+    }
+}
+
+impl PartialOrd for Version {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Version {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Version {}
+
+/// Sürüm + PiSi yayın (release) numarası. Önce sürüm, eşitse yayın karşılaştırılır.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct PackageVersion {
+    pub version: Version,
+    pub release: u32,
+}
+
+impl PackageVersion {
+    pub fn new(version: &str, release: u32) -> Result<Self> {
+        Ok(Self { version: Version::parse(version)?, release })
+    }
+}
+
+impl fmt::Display for PackageVersion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.version, self.release)
+    }
+}
+
+/// İki sürüm dizgesini PiSi kurallarıyla karşılaştırır.
+/// Geçersiz sürümlerde düz metin karşılaştırmasına düşer.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    match (Version::parse(a), Version::parse(b)) {
+        (Ok(a), Ok(b)) => a.cmp(&b),
+        _ => a.cmp(b),
+    }
+}
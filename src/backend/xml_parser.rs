@@ -1,11 +1,20 @@
 use serde::{Serialize, Deserialize};
 use anyhow::Result;
 use roxmltree::Document;
-use std::collections::HashMap;
-use std::path::Path;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
 use std::fs;
+use std::io::BufRead;
 use once_cell::sync::Lazy;
 use std::sync::Mutex;
+use crate::backend::index_stream::PackageStream;
+use crate::backend::compression::open_index;
+use crate::backend::dependency::DependencyConstraint;
+use crate::backend::version::PackageVersion;
+use crate::backend::error::PmError;
+use crate::backend::index_cache::{CachedRepository, IndexCache, SourceKey};
+use crate::backend::index_verify::IndexVerifier;
+use crate::config::settings::AppSettings;
 
 // RAM Önbelleği (Uygulama açıkken tekrar parse etmemek için)
 static CACHED_PACKAGES: Lazy<Mutex<Option<Vec<PackageInfo>>>> = Lazy::new(|| Mutex::new(None));
@@ -29,6 +38,26 @@ pub struct PackageInfo {
     pub source: Option<Source>,
     pub history: Vec<PackageHistory>,
     pub dependencies: Vec<Dependency>,
+    pub build_dependencies: Vec<Dependency>,
+    /// Bu paket kurulursa kaldırılması gereken paketler
+    pub conflicts: Vec<Dependency>,
+    /// Bu paketin yerini aldığı (kurulunca kaldırılacak) eski paketler
+    pub replaces: Vec<Dependency>,
+    pub provides: Provides,
+    /// `app:gui`, `app:console`, `library` gibi paket tipleri
+    pub is_a: Vec<String>,
+    pub packager: Option<Packager>,
+    pub package_uri: Option<String>,
+    pub package_hash: Option<String>,
+    /// Paketin geldiği deponun adı (ör. "stable2", "contrib")
+    pub repository: String,
+}
+
+impl PackageInfo {
+    /// Paketin güncel sürüm + yayın numarası; sürüm PiSi kurallarına uymuyorsa `None`
+    pub fn package_version(&self) -> Option<PackageVersion> {
+        PackageVersion::new(&self.version, self.release).ok()
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -37,18 +66,54 @@ pub struct Source {
     pub homepage: String,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Packager {
+    pub name: String,
+    pub email: String,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PackageHistory {
     pub version: String,
     pub release: u32,
     pub date: String,
+    pub comment: String,
+    /// Bu sürümü hazırlayan paketçi
+    pub packager: Option<Packager>,
+}
+
+/// `<Provides>` altında paketin sağladığı COMAR betikleri ve pkg-config modülleri
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Provides {
+    pub comar: Vec<ComarProvide>,
+    pub pkg_config: Vec<PkgConfigProvide>,
+    pub pkg_config32: Vec<PkgConfigProvide>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Dependency {
+pub struct ComarProvide {
+    /// COMAR nesne modeli (ör. `System.Package`, `System.Service`)
+    pub om: String,
+    pub script: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PkgConfigProvide {
     pub name: String,
     pub version: Option<String>,
-    pub release: Option<u32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dependency {
+    pub name: String,
+    pub constraint: DependencyConstraint,
+}
+
+impl Dependency {
+    /// Verilen sürüm/yayındaki bir paket bu bağımlılığı karşılıyor mu?
+    pub fn is_satisfied_by(&self, version: &str, release: u32) -> bool {
+        self.constraint.is_satisfied_by(version, release)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -58,11 +123,27 @@ pub struct Component {
     pub package_count: usize,
 }
 
+/// `/var/lib/pisi/index/<repo>/pisi-index.xml` altında bulunan bir depo indeksi
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RepositoryIndex {
+    pub name: String,
+    pub path: PathBuf,
+    /// Küçük değer daha yüksek öncelik demektir (PiSi depo sırası)
+    pub priority: usize,
+}
+
 pub struct XmlParser;
 
 impl XmlParser {
-    const INDEX_PATH: &'static str = "/var/lib/pisi/index/stable2/pisi-index.xml";
-    const CACHE_PATH: &'static str = "/tmp/pisi-pm-index-cache.bin";
+    pub(crate) const INDEX_DIR: &'static str = "/var/lib/pisi/index";
+    /// Depo dizininde aranacak indeks dosyaları (öncelik sırasıyla)
+    const INDEX_FILES: [&'static str; 4] = [
+        "pisi-index.xml",
+        "pisi-index.xml.xz",
+        "pisi-index.xml.zst",
+        "pisi-index.xml.gz",
+    ];
+    const REPO_ORDER_PATH: &'static str = "/var/lib/pisi/info/repos";
 
     pub fn load_pisi_index() -> Result<Vec<PackageInfo>> {
         // 1. Durum: RAM'de var mı?
@@ -76,71 +157,212 @@ impl XmlParser {
 
         println!("RAM Cache MISS. Checking Disk Cache...");
 
-        // 2. Durum: Disk önbelleği (Binary Cache) kontrolü
-        if let Some(cached) = Self::load_from_binary_cache()? {
-            println!("Disk Cache HIT: {} packages", cached.len());
-            let mut cache = CACHED_PACKAGES.lock().unwrap();
-            *cache = Some(cached.clone());
-            return Ok(cached);
+        let repos = Self::discover_repositories()?;
+        if repos.is_empty() {
+            return Err(PmError::IndexMissing {
+                message: format!("No Pisi repository index found under {}", Self::INDEX_DIR),
+            }
+            .into());
         }
 
-        // 3. Durum: Hiçbiri yoksa XML'den parse et
-        println!("No valid cache found. Parsing XML from: {}", Self::INDEX_PATH);
-        let xml_content = fs::read_to_string(Self::INDEX_PATH)
-            .map_err(|e| anyhow::anyhow!("Failed to read Pisi index file: {}", e))?;
-        
-        let packages = Self::parse_pisi_index(&xml_content)?;
-        
-        // Diske kaydet
-        Self::save_to_binary_cache(&packages)?;
-        
+        // 2. Durum: Disk önbelleği
+        let disk_cache = IndexCache::default_path().map(IndexCache::new);
+        if disk_cache.is_none() {
+            println!("No user cache directory (XDG_CACHE_HOME/HOME unset), disk cache disabled");
+        }
+        let verifier = IndexVerifier::from_settings(&AppSettings::load());
+        let packages = Self::load_verified_repositories(&repos, &verifier, disk_cache.as_ref())?;
+
         // RAM'e kaydet
         let mut cache = CACHED_PACKAGES.lock().unwrap();
         *cache = Some(packages.clone());
-        
+
         Ok(packages)
     }
 
+    /// RAM önbelleğini boşaltır; depo kümesi değiştiğinde bir sonraki yükleme diskten yapılır
+    pub fn clear_memory_cache() {
+        *CACHED_PACKAGES.lock().unwrap() = None;
+    }
+
     pub fn get_components() -> Result<Vec<Component>> {
         let packages = Self::load_pisi_index()?;
         Ok(Self::parse_components(&packages))
     }
 
-    fn load_from_binary_cache() -> Result<Option<Vec<PackageInfo>>> {
-        let xml_path = Path::new(Self::INDEX_PATH);
-        let cache_path = Path::new(Self::CACHE_PATH);
+    /// `/var/lib/pisi/index/*/` altındaki depoları PiSi depo sırasına göre döner.
+    /// Sıra `/var/lib/pisi/info/repos` dosyasından okunur; orada olmayan depolar
+    /// alfabetik olarak sona eklenir, pasif (inactive) depolar atlanır.
+    pub fn discover_repositories() -> Result<Vec<RepositoryIndex>> {
+        Self::discover_repositories_in(Path::new(Self::INDEX_DIR), Path::new(Self::REPO_ORDER_PATH))
+    }
+
+    pub fn discover_repositories_in(index_dir: &Path, order_path: &Path) -> Result<Vec<RepositoryIndex>> {
+        if !index_dir.is_dir() {
+            return Ok(Vec::new());
+        }
 
-        if !xml_path.exists() || !cache_path.exists() {
-            return Ok(None);
+        let mut found = Vec::new();
+        for entry in fs::read_dir(index_dir)? {
+            let entry = entry?;
+            if let Some(path) = Self::find_index_file(&entry.path()) {
+                found.push((entry.file_name().to_string_lossy().to_string(), path));
+            }
         }
+        found.sort_by(|a, b| a.0.cmp(&b.0));
+
+        let (order, inactive) = match fs::read_to_string(order_path) {
+            Ok(content) => Self::parse_repo_order(&content)?,
+            Err(_) => (Vec::new(), Vec::new()),
+        };
+
+        found.retain(|(name, _)| !inactive.contains(name));
+        found.sort_by_key(|(name, _)| order.iter().position(|o| o == name).unwrap_or(usize::MAX));
+
+        Ok(found
+            .into_iter()
+            .enumerate()
+            .map(|(priority, (name, path))| RepositoryIndex { name, path, priority })
+            .collect())
+    }
+
+    /// Depo dizinindeki indeks dosyasını (`INDEX_FILES` sırasıyla) bulur
+    pub fn find_index_file(repo_dir: &Path) -> Option<PathBuf> {
+        Self::INDEX_FILES
+            .iter()
+            .map(|file| repo_dir.join(file))
+            .find(|path| path.is_file())
+    }
 
-        // Tarih kontrolü: Eğer XML, Cache'den daha yeniyse geçersizdir
-        let xml_mtime = fs::metadata(xml_path)?.modified()?;
-        let cache_mtime = fs::metadata(cache_path)?.modified()?;
+    /// PiSi'nin `<REPOS><Repo><Name/><Status/></Repo></REPOS>` dosyasından
+    /// (sıralı aktif depolar, pasif depolar) çiftini çıkarır
+    fn parse_repo_order(xml_content: &str) -> Result<(Vec<String>, Vec<String>)> {
+        let doc = Document::parse(xml_content)?;
+        let mut order = Vec::new();
+        let mut inactive = Vec::new();
 
-        if xml_mtime > cache_mtime {
-            println!("XML index updated, cache is stale.");
-            return Ok(None);
+        for repo in doc.root_element().children().filter(|n| n.has_tag_name("Repo")) {
+            let name = match Self::get_text(&repo, "Name") {
+                Some(name) if !name.is_empty() => name,
+                _ => continue,
+            };
+            if Self::get_text(&repo, "Status").as_deref() == Some("inactive") {
+                inactive.push(name);
+            } else {
+                order.push(name);
+            }
         }
 
-        // Binary cache'i oku
-        println!("Loading packages from binary cache: {}", Self::CACHE_PATH);
-        let bytes = fs::read(cache_path)?;
-        let packages: Vec<PackageInfo> = bincode::deserialize(&bytes)
-            .map_err(|e| anyhow::anyhow!("Failed to deserialize cache: {}", e))?;
+        Ok((order, inactive))
+    }
 
-        Ok(Some(packages))
+    /// Verilen depoların indekslerini parse edip öncelik sırasına göre birleştirir.
+    /// Her paketin `repository` alanı geldiği depoyu gösterir. Okunamayan bir depo
+    /// atlanır; hiçbir depo okunamazsa ilk hata döner.
+    pub fn load_repositories(repos: &[RepositoryIndex]) -> Result<Vec<PackageInfo>> {
+        let mut sorted: Vec<&RepositoryIndex> = repos.iter().collect();
+        sorted.sort_by_key(|repo| repo.priority);
+
+        let mut per_repo = Vec::new();
+        let mut failed = None;
+        for repo in sorted {
+            match Self::load_repository(repo) {
+                Ok(packages) => per_repo.push(packages),
+                Err(e) => {
+                    println!("Skipping repository {}: {}", repo.name, e);
+                    failed.get_or_insert(e);
+                }
+            }
+        }
+        if let (true, Some(e)) = (per_repo.is_empty(), failed) {
+            return Err(e);
+        }
+
+        Ok(Self::merge_repositories(per_repo))
     }
 
-    fn save_to_binary_cache(packages: &[PackageInfo]) -> Result<()> {
-        println!("Saving packages to binary cache...");
-        let bytes = bincode::serialize(packages)
-            .map_err(|e| anyhow::anyhow!("Failed to serialize cache: {}", e))?;
-        
-        fs::write(Self::CACHE_PATH, bytes)
-            .map_err(|e| anyhow::anyhow!("Failed to write cache file: {}", e))?;
-        
-        Ok(())
+    /// Depoları disk önbelleğinden veya (önbellekte olmayan ya da değişmiş olanları)
+    /// XML'den yükleyip öncelik sırasına göre birleştirir. Her depo, indeks dosyasının
+    /// boyutu ve içerik özetiyle eşleştirilir.
+    ///
+    /// Yeniden parse edilecek depolar önce doğrulanır; SHA1 veya imzası tutmayan ya da
+    /// indeksi okunamayan bir depo atlanır ve önbelleğe yazılmaz, diğerleri kullanılmaya
+    /// devam eder. Doğrulama durumu depo listesinde (`RepositoryInfo::trust`) görünür.
+    /// Hiçbir depo kullanılamazsa ilk hata döner.
+    pub fn load_verified_repositories(
+        repos: &[RepositoryIndex],
+        verifier: &IndexVerifier,
+        disk_cache: Option<&IndexCache>,
+    ) -> Result<Vec<PackageInfo>> {
+        let keys = repos
+            .iter()
+            .map(|repo| Ok((repo.name.clone(), SourceKey::of_file(&repo.path)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let mut cached = disk_cache.map(|c| c.load(&keys)).unwrap_or_default();
+
+        let stale = repos.iter().filter(|r| !cached.contains_key(&r.name)).count();
+        if stale == 0 {
+            println!("Disk Cache HIT: {} repositories", repos.len());
+        } else {
+            println!("Parsing XML of {} of {} repositories", stale, repos.len());
+        }
+
+        let mut sorted: Vec<(&RepositoryIndex, SourceKey)> = repos.iter().zip(keys.into_iter().map(|(_, key)| key)).collect();
+        sorted.sort_by_key(|(repo, _)| repo.priority);
+        let mut entries = Vec::new();
+        let mut failed = None;
+        for (repo, key) in sorted {
+            let packages = match cached.remove(&repo.name) {
+                Some(packages) => packages,
+                None => match verifier.check_repository(repo).map_err(anyhow::Error::from).and_then(|_| Self::load_repository(repo)) {
+                    Ok(packages) => packages,
+                    Err(e) => {
+                        println!("Skipping repository {}: {}", repo.name, e);
+                        failed.get_or_insert(e);
+                        continue;
+                    }
+                },
+            };
+            entries.push(CachedRepository { name: repo.name.clone(), key, packages });
+        }
+        if let (true, Some(e)) = (entries.is_empty(), failed) {
+            return Err(e);
+        }
+
+        // Diske kaydet; önbellek yazılamazsa paketler yine de kullanılabilir
+        if let (true, Some(disk_cache)) = (stale > 0, disk_cache) {
+            if let Err(e) = disk_cache.save(&entries) {
+                println!("{}", e);
+            }
+        }
+        Ok(Self::merge_repositories(entries.into_iter().map(|entry| entry.packages).collect()))
+    }
+
+    /// Tek bir deponun indeksini parse eder; paketlerin `repository` alanı depo adıdır
+    pub fn load_repository(repo: &RepositoryIndex) -> Result<Vec<PackageInfo>> {
+        println!("Parsing XML from: {}", repo.path.display());
+        let mut packages = Self::load_index_file(&repo.path)?;
+        for package in &mut packages {
+            package.repository = repo.name.clone();
+        }
+        Ok(packages)
+    }
+
+    /// Öncelik sırasına göre verilmiş depo paket listelerini birleştirir.
+    /// Aynı isimli paket birden fazla depoda varsa önceliği yüksek olan kazanır.
+    pub fn merge_repositories(per_repo: Vec<Vec<PackageInfo>>) -> Vec<PackageInfo> {
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut merged = Vec::new();
+
+        for repo_packages in per_repo {
+            for package in repo_packages {
+                if seen.insert(package.name.clone()) {
+                    merged.push(package);
+                }
+            }
+        }
+
+        merged
     }
 
     pub fn parse_pisi_index(xml_content: &str) -> Result<Vec<PackageInfo>> {
@@ -149,49 +371,7 @@ impl XmlParser {
 
         // Sadece kök düğüm altındaki <Package> tag'lerini bul (Performans için)
         for node in doc.root_element().children().filter(|n| n.has_tag_name("Package")) {
-
-            let package_name = Self::get_text(&node, "Name")
-                .unwrap_or_else(|| "Unknown".to_string());
-            
-            let part_of = Self::get_text(&node, "PartOf").unwrap_or_else(|| "system".to_string());
-            
-            let history = Self::parse_history(&node);
-            let (version, release) = if let Some(latest) = history.first() {
-                (latest.version.clone(), latest.release)
-            } else {
-                (String::new(), 0)
-            };
-
-            let package_size = Self::get_text(&node, "PackageSize")
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(0);
-                
-            let installed_size = Self::get_text(&node, "InstalledSize")
-                .and_then(|s| s.parse().ok())
-                .unwrap_or(0);
-
-            let package = PackageInfo {
-                name: package_name.clone(),
-                summary: Self::get_multilang_text(&node, "Summary").unwrap_or_default(),
-                description: Self::get_multilang_text(&node, "Description").unwrap_or_default(),
-                version,
-                release,
-                license: Self::get_text(&node, "License").unwrap_or_default(),
-                part_of: part_of.clone(),
-                package_size,
-                installed_size,
-                package_format: Self::get_text(&node, "PackageFormat").unwrap_or_else(|| "1.2".to_string()),
-                distribution: Self::get_text(&node, "Distribution").unwrap_or_else(|| "PisiLinux".to_string()),
-                distribution_release: Self::get_text(&node, "DistributionRelease").unwrap_or_else(|| "2.0".to_string()),
-                architecture: Self::get_text(&node, "Architecture").unwrap_or_else(|| "x86_64".to_string()),
-                icon: Self::get_text(&node, "Icon"),
-                source: Self::parse_source(&node),
-                history,
-                dependencies: Self::parse_dependencies(&node),
-            };
-            
-            // Sadece geçerli paketleri ekle (isim ve versiyonu olan)
-            if package_name != "Unknown" && !package.version.is_empty() {
+            if let Some(package) = Self::parse_package_node(&node) {
                 packages.push(package);
             }
         }
@@ -200,6 +380,91 @@ impl XmlParser {
         Ok(packages)
     }
 
+    /// İndeksi tek bir DOM ağacı kurmadan, `<Package>` elemanlarını tek tek okuyarak parse eder
+    pub fn parse_pisi_index_reader<R: BufRead>(reader: R) -> Result<Vec<PackageInfo>> {
+        let packages = PackageStream::new(reader).collect::<Result<Vec<_>>>()?;
+
+        println!("Successfully parsed {} valid packages from Pisi index", packages.len());
+        Ok(packages)
+    }
+
+    /// Tek bir indeks dosyasını (düz, `.xz`, `.zst` veya `.gz`) önbelleğe dokunmadan parse eder.
+    /// Depo eklenmeden önce içeriğini önizlemek için de kullanılır.
+    pub fn load_index_file(path: &Path) -> Result<Vec<PackageInfo>> {
+        Self::parse_pisi_index_reader(open_index(path)?)
+    }
+
+    /// Tek bir `<Package>` düğümünü `PackageInfo`'ya çevirir.
+    /// İsmi veya versiyonu olmayan paketler için `None` döner.
+    pub(crate) fn parse_package_node(node: &roxmltree::Node) -> Option<PackageInfo> {
+        let package_name = Self::get_text(node, "Name")
+            .unwrap_or_else(|| "Unknown".to_string());
+
+        let part_of = Self::get_text(node, "PartOf").unwrap_or_else(|| "system".to_string());
+
+        let history = Self::parse_history(node);
+        let (version, release) = if let Some(latest) = history.first() {
+            (latest.version.clone(), latest.release)
+        } else {
+            (String::new(), 0)
+        };
+
+        let package_size = Self::get_text(node, "PackageSize")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+
+        let installed_size = Self::get_text(node, "InstalledSize")
+            .and_then(|s| s.parse().ok())
+            .unwrap_or(0);
+
+        let package = PackageInfo {
+            name: package_name.clone(),
+            summary: Self::get_multilang_text(node, "Summary").unwrap_or_default(),
+            description: Self::get_multilang_text(node, "Description").unwrap_or_default(),
+            version,
+            release,
+            license: Self::get_text(node, "License").unwrap_or_default(),
+            part_of,
+            package_size,
+            installed_size,
+            package_format: Self::get_text(node, "PackageFormat").unwrap_or_else(|| "1.2".to_string()),
+            distribution: Self::get_text(node, "Distribution").unwrap_or_else(|| "PisiLinux".to_string()),
+            distribution_release: Self::get_text(node, "DistributionRelease").unwrap_or_else(|| "2.0".to_string()),
+            architecture: Self::get_text(node, "Architecture").unwrap_or_else(|| "x86_64".to_string()),
+            icon: Self::get_text(node, "Icon"),
+            source: Self::parse_source(node),
+            history,
+            dependencies: Self::parse_dependencies(node, "RuntimeDependencies", "Dependency"),
+            // Derleme bağımlılıkları paketin değil, kaynağının (`<Source>`) altındadır
+            build_dependencies: node.children()
+                .find(|n| n.has_tag_name("Source"))
+                .map(|source| Self::parse_dependencies(&source, "BuildDependencies", "Dependency"))
+                .unwrap_or_default(),
+            conflicts: Self::parse_dependencies(node, "Conflicts", "Package"),
+            replaces: Self::parse_dependencies(node, "Replaces", "Package"),
+            provides: Self::parse_provides(node),
+            is_a: node.children()
+                .filter(|n| n.has_tag_name("IsA"))
+                .filter_map(|n| n.text())
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect(),
+            packager: node.descendants()
+                .find(|n| n.has_tag_name("Packager"))
+                .and_then(|n| Self::parse_packager(&n)),
+            package_uri: Self::get_text(node, "PackageURI"),
+            package_hash: Self::get_text(node, "PackageHash"),
+            repository: String::new(),
+        };
+
+        // Sadece geçerli paketleri ekle (isim ve versiyonu olan)
+        if package_name != "Unknown" && !package.version.is_empty() {
+            Some(package)
+        } else {
+            None
+        }
+    }
+
     fn get_text(node: &roxmltree::Node, tag_name: &str) -> Option<String> {
         // Önce direk child'larda ara
         for child in node.children() {
@@ -340,9 +605,11 @@ impl XmlParser {
                     .unwrap_or(1);
                 let version = Self::get_text(&update_node, "Version").unwrap_or_default();
                 let date = Self::get_text(&update_node, "Date").unwrap_or_default();
+                let comment = Self::get_text(&update_node, "Comment").unwrap_or_default();
+                let packager = Self::parse_packager(&update_node);
                 
                 if !version.is_empty() {
-                    history.push(PackageHistory { version, release, date });
+                    history.push(PackageHistory { version, release, date, comment, packager });
                 }
             }
         }
@@ -350,19 +617,64 @@ impl XmlParser {
         history
     }
 
-    fn parse_dependencies(node: &roxmltree::Node) -> Vec<Dependency> {
+    /// `<Name>`/`<Email>` çocuklarını taşıyan bir düğümden paketçi bilgisini çıkarır
+    fn parse_packager(node: &roxmltree::Node) -> Option<Packager> {
+        let child_text = |tag: &str| node.children()
+            .find(|n| n.has_tag_name(tag))
+            .and_then(|n| n.text())
+            .map(|s| s.trim().to_string());
+
+        let name = child_text("Name").unwrap_or_default();
+        let email = child_text("Email").unwrap_or_default();
+
+        if name.is_empty() && email.is_empty() {
+            None
+        } else {
+            Some(Packager { name, email })
+        }
+    }
+
+    fn parse_provides(node: &roxmltree::Node) -> Provides {
+        let mut provides = Provides::default();
+
+        if let Some(provides_node) = node.children().find(|n| n.has_tag_name("Provides")) {
+            for child in provides_node.children().filter(|n| n.is_element()) {
+                let text = child.text().unwrap_or("").trim().to_string();
+                if text.is_empty() {
+                    continue;
+                }
+                let pkg_config = || PkgConfigProvide {
+                    name: text.clone(),
+                    version: child.attribute("version").map(|s| s.to_string()),
+                };
+
+                match child.tag_name().name() {
+                    "COMAR" => provides.comar.push(ComarProvide {
+                        om: text.clone(),
+                        script: child.attribute("script").map(|s| s.to_string()),
+                    }),
+                    "PkgConfig" => provides.pkg_config.push(pkg_config()),
+                    "PkgConfig32" => provides.pkg_config32.push(pkg_config()),
+                    _ => {}
+                }
+            }
+        }
+
+        provides
+    }
+
+    /// `<RuntimeDependencies>`, `<BuildDependencies>`, `<Conflicts>`, `<Replaces>` gibi
+    /// listeleri okur. Hepsi aynı version/release özniteliklerini kullanır.
+    fn parse_dependencies(node: &roxmltree::Node, list_tag: &str, item_tag: &str) -> Vec<Dependency> {
         let mut deps = Vec::new();
         
-        if let Some(deps_node) = node.children().find(|n| n.has_tag_name("RuntimeDependencies")) {
-            for dep_node in deps_node.children().filter(|n| n.has_tag_name("Dependency")) {
+        if let Some(deps_node) = node.children().find(|n| n.has_tag_name(list_tag)) {
+            for dep_node in deps_node.children().filter(|n| n.has_tag_name(item_tag)) {
                 let name = dep_node.text().unwrap_or("").trim().to_string();
-                let version = dep_node.attribute("version").map(|s| s.to_string())
-                    .or_else(|| dep_node.attribute("versionFrom").map(|s| s.to_string()));
-                let release = dep_node.attribute("release").and_then(|s| s.parse().ok())
-                    .or_else(|| dep_node.attribute("releaseFrom").and_then(|s| s.parse().ok()));
+                let constraint = DependencyConstraint::from_node(&dep_node);
                 
                 if !name.is_empty() {
-                    deps.push(Dependency { name, version, release });
+                    deps.push(Dependency { name, constraint });
                 }
             }
         }
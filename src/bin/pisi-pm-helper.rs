@@ -0,0 +1,42 @@
+// pkexec ile root olarak çalıştırılan yardımcı program.
+// Sadece izinli pisi komutlarını iletir; arayüz sürecinin kendisi yetkisiz çalışır.
+use pm_core::backend::privileged::{self, SIGNAL_VERB};
+use std::os::unix::process::CommandExt;
+use std::process::{exit, Command};
+
+fn main() {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+
+    if let Err(e) = privileged::validate_helper_args(&args) {
+        eprintln!("pisi-pm-helper: {}", e);
+        exit(2);
+    }
+
+    if args[0] == SIGNAL_VERB {
+        send_signal(&args[1..]);
+        return;
+    }
+
+    // pkexec ortamı temizlediği için dil ayarı burada yeniden verilir (çıktı ayrıştırması için)
+    let error = Command::new("pisi").args(&args).env("LC_ALL", "C").exec();
+    eprintln!("pisi-pm-helper: failed to run pisi: {}", error);
+    exit(127);
+}
+
+fn send_signal(args: &[String]) {
+    let (signal, pid) = match privileged::parse_signal_args(args) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            eprintln!("pisi-pm-helper: {}", e);
+            exit(2);
+        }
+    };
+    if !privileged::is_pisi_process(pid) {
+        eprintln!("pisi-pm-helper: process {} is not pisi", pid);
+        exit(2);
+    }
+    if unsafe { libc::kill(pid as libc::pid_t, signal) } != 0 {
+        eprintln!("pisi-pm-helper: {}", std::io::Error::last_os_error());
+        exit(1);
+    }
+}
@@ -1,4 +1,7 @@
+use anyhow::Result;
 use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
 
 // SettingsTab'ı basitleştiriyoruz
 #[derive(Debug, Clone, PartialEq)]
@@ -33,6 +36,10 @@ pub struct UpdateSettings {
 pub struct CacheSettings {
     pub use_disk_cache: bool,
     pub cache_size_mb: u32,
+    /// pisi'nin indirilen paketleri sakladığı dizin (`pisi.conf` `packages_dir`).
+    /// Root'a aittir ve pisi tarafından temizlenir; bu yüzden uygulamanın kendi
+    /// önbellekleri (indeks ve yansı testi) burada değil `AppSettings::cache_dir`
+    /// altında tutulur.
     pub cache_directory: String,
 }
 
@@ -63,6 +70,20 @@ pub struct AppSettings {
     pub proxy: ProxySettings,
     pub repositories: Vec<Repository>,
     pub system_tray_enabled: bool,
+    /// Yükseltmelerde atlanan (sabitlenmiş) paketler
+    #[serde(default)]
+    pub held_packages: Vec<String>,
+    /// Hız testinde denenecek yansıların listesi
+    #[serde(default = "default_mirror_list")]
+    pub mirror_list: String,
+    /// Depo indekslerinin imzalarını doğrulamak için kullanılan GPG anahtarlığı;
+    /// boşsa sadece SHA1 denetlenir
+    #[serde(default)]
+    pub index_keyring: Option<String>,
+}
+
+fn default_mirror_list() -> String {
+    "/usr/share/pisi-package-manager/mirrors.list".to_string()
 }
 
 impl Default for AppSettings {
@@ -106,8 +127,79 @@ impl Default for AppSettings {
                 },
             ],
             system_tray_enabled: true,
+            held_packages: Vec::new(),
+            mirror_list: default_mirror_list(),
+            index_keyring: None,
+        }
+    }
+}
+
+impl AppSettings {
+    /// `$XDG_CONFIG_HOME/pisi-package-manager/settings.json` (yoksa `~/.config/...`).
+    /// İkisi de tanımlı değilse (pkexec veya sadeleştirilmiş root ortamı) `None`;
+    /// herkesin yazabildiği `/tmp` gibi bir dizine düşülmez.
+    pub fn config_path() -> Option<PathBuf> {
+        Some(user_dir("XDG_CONFIG_HOME", ".config")?.join("settings.json"))
+    }
+
+    /// `$XDG_CACHE_HOME/pisi-package-manager` (yoksa `~/.cache/...`).
+    /// İkisi de tanımlı değilse `None`; bu durumda disk önbellekleri kullanılmaz.
+    pub fn cache_dir() -> Option<PathBuf> {
+        user_dir("XDG_CACHE_HOME", ".cache")
+    }
+
+    /// Kayıtlı ayarları okur; dosya yoksa veya bozuksa varsayılanlar kullanılır
+    pub fn load() -> Self {
+        let Some(path) = Self::config_path() else {
+            return Self::default();
+        };
+        match fs::read_to_string(&path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                println!("Invalid settings file {}: {}, using defaults", path.display(), e);
+                Self::default()
+            }),
+            Err(_) => Self::default(),
+        }
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::config_path()
+            .ok_or_else(|| anyhow::anyhow!("Neither XDG_CONFIG_HOME nor HOME is set, cannot save settings"))?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
         }
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
+    pub fn is_held(&self, package: &str) -> bool {
+        self.held_packages.iter().any(|p| p == package)
     }
+
+    /// Paketi sabitler; zaten sabitliyse `false` döner
+    pub fn hold(&mut self, package: &str) -> bool {
+        if self.is_held(package) {
+            return false;
+        }
+        self.held_packages.push(package.to_string());
+        self.held_packages.sort();
+        true
+    }
+
+    /// Sabitlemeyi kaldırır; paket sabitli değilse `false` döner
+    pub fn unhold(&mut self, package: &str) -> bool {
+        let before = self.held_packages.len();
+        self.held_packages.retain(|p| p != package);
+        self.held_packages.len() != before
+    }
+}
+
+/// `$<xdg_var>/pisi-package-manager` veya `$HOME/<home_subdir>/pisi-package-manager`.
+/// Sadece mutlak yollar kabul edilir.
+fn user_dir(xdg_var: &str, home_subdir: &str) -> Option<PathBuf> {
+    let absolute = |var: &str| std::env::var_os(var).map(PathBuf::from).filter(|p| p.is_absolute());
+    let base = absolute(xdg_var).or_else(|| absolute("HOME").map(|home| home.join(home_subdir)))?;
+    Some(base.join("pisi-package-manager"))
 }
 
 #[derive(Debug, Clone)]
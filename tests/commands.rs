@@ -0,0 +1,580 @@
+use pm_core::backend::actions::{PackageActions, UpgradablePackage};
+use pm_core::backend::error::PmError;
+use pm_core::backend::index_verify::TrustStatus;
+use pm_core::backend::installed_db::InstalledDb;
+use pm_core::backend::names::{PackageName, RepoName};
+use pm_core::backend::operation::{OperationPhase, OperationProgress, OperationRegistry};
+use pm_core::backend::repo_probe::SystemDistribution;
+use pm_core::backend::repository::{MoveDirection, RepositoryInfo, RepositoryManager};
+use pm_core::backend::runner::{PisiOutput, ScriptedPisiRunner};
+use pm_core::backend::transaction::{PackageResultStatus, TransactionAction, TransactionActionKind};
+use pm_core::backend::xml_parser::XmlParser;
+use pm_core::config::settings::AppSettings;
+use std::path::PathBuf;
+
+mod common;
+use common::{fixtures, has_gpgv};
+
+fn fixture(name: &str) -> String {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pisi").join(name);
+    std::fs::read_to_string(path).unwrap()
+}
+
+fn fixture_db() -> InstalledDb {
+    InstalledDb::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/root"))
+}
+
+fn package(name: &str) -> PackageName {
+    PackageName::new(name).unwrap()
+}
+
+fn calls(runner: &ScriptedPisiRunner) -> Vec<String> {
+    runner.calls().iter().map(|c| c.join(" ")).collect()
+}
+
+fn phases(progress: &[OperationProgress]) -> Vec<OperationPhase> {
+    let mut phases: Vec<_> = progress.iter().map(|p| p.phase).collect();
+    phases.dedup();
+    phases
+}
+
+const NOT_FOUND: &str = "Error: Package nano not found in any active repository.\n";
+
+#[tokio::test]
+async fn install_reports_progress() {
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["it", "-y", "--", "nano"], PisiOutput::success(fixture("install_nano.out")));
+    let registry = OperationRegistry::default();
+    let mut progress = Vec::new();
+
+    let message = PackageActions::new(&runner, &registry, fixture_db())
+        .install(&package("nano"), |p| progress.push(p))
+        .await
+        .unwrap();
+
+    assert_eq!(message, "Package nano installed successfully");
+    assert_eq!(calls(&runner), ["it -y -- nano"]);
+    assert_eq!(phases(&progress), [
+        OperationPhase::Started,
+        OperationPhase::Downloading,
+        OperationPhase::Installing,
+        OperationPhase::Extracting,
+        OperationPhase::Installing,
+        OperationPhase::Configuring,
+        OperationPhase::Finished,
+    ]);
+    // `\r` ile yeniden yazılan indirme satırı iki ayrı bildirim üretir
+    let percents: Vec<_> = progress.iter().filter_map(|p| p.percent).collect();
+    assert_eq!(&percents[..2], [42, 100]);
+    assert!(registry.list().is_empty());
+}
+
+#[tokio::test]
+async fn install_failure_is_classified() {
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["it", "-y", "--", "nano"], PisiOutput::failure(1, NOT_FOUND));
+    let registry = OperationRegistry::default();
+    let mut progress = Vec::new();
+
+    let error = PackageActions::new(&runner, &registry, fixture_db())
+        .install(&package("nano"), |p| progress.push(p))
+        .await
+        .unwrap_err();
+
+    assert_eq!(error, PmError::PackageNotFound { message: NOT_FOUND.trim().to_string() });
+    let last = progress.last().unwrap();
+    assert_eq!(last.phase, OperationPhase::Failed);
+    assert_eq!(last.message, NOT_FOUND.trim());
+}
+
+#[tokio::test]
+async fn unexpected_pisi_call_is_an_error() {
+    let runner = ScriptedPisiRunner::new();
+    let registry = OperationRegistry::default();
+
+    let error = PackageActions::new(&runner, &registry, fixture_db())
+        .install(&package("nano"), |_| {})
+        .await
+        .unwrap_err();
+    assert!(matches!(&error, PmError::Internal { message } if message.contains("Unexpected pisi call: it -y -- nano")));
+}
+
+#[tokio::test]
+async fn remove_refuses_to_break_installed_dependents() {
+    let runner = ScriptedPisiRunner::new();
+    let registry = OperationRegistry::default();
+
+    let error = PackageActions::new(&runner, &registry, fixture_db())
+        .remove(&package("glibc"), false, |_| {})
+        .await
+        .unwrap_err();
+
+    assert_eq!(error, PmError::DependencyConflict {
+        message: "Removing glibc would break installed packages: bash, nano".to_string(),
+    });
+    assert!(runner.calls().is_empty());
+}
+
+#[tokio::test]
+async fn remove_with_force_and_without_dependents() {
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["rm", "-y", "--", "glibc"], PisiOutput::success(""))
+        .expect(&["rm", "-y", "--", "nano"], PisiOutput::success(fixture("remove_nano.out")));
+    let registry = OperationRegistry::default();
+    let actions = PackageActions::new(&runner, &registry, fixture_db());
+    let mut progress = Vec::new();
+
+    actions.remove(&package("glibc"), true, |_| {}).await.unwrap();
+    let message = actions.remove(&package("nano"), false, |p| progress.push(p)).await.unwrap();
+
+    assert_eq!(message, "Package nano removed successfully");
+    assert_eq!(calls(&runner), ["rm -y -- glibc", "rm -y -- nano"]);
+    assert!(progress.iter().any(|p| p.phase == OperationPhase::Removing && p.package.as_deref() == Some("nano")));
+}
+
+#[tokio::test]
+async fn remove_failure_keeps_unclassified_stderr() {
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["rm", "-y", "--", "nano"], PisiOutput::failure(1, "Error: nano is not installed\n"));
+    let registry = OperationRegistry::default();
+
+    let error = PackageActions::new(&runner, &registry, fixture_db())
+        .remove(&package("nano"), false, |_| {})
+        .await
+        .unwrap_err();
+    assert_eq!(error, PmError::Pisi { exit_code: 1, message: "Error: nano is not installed".to_string() });
+}
+
+#[tokio::test]
+async fn update_success_and_failure() {
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["up", "-y", "--", "bash"], PisiOutput::success("Upgraded bash\n"))
+        .expect(&["up", "-y", "--", "bash"], PisiOutput::failure(1, "Error: bash is already up to date\n"));
+    let registry = OperationRegistry::default();
+    let actions = PackageActions::new(&runner, &registry, fixture_db());
+
+    assert_eq!(actions.update(&package("bash"), |_| {}).await.unwrap(), "Package bash updated successfully");
+    let error = actions.update(&package("bash"), |_| {}).await.unwrap_err();
+    assert_eq!(error.to_string(), "Error: bash is already up to date");
+}
+
+#[tokio::test]
+async fn update_repo_success_and_failure() {
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["ur"], PisiOutput::success(fixture("update_repo.out")))
+        .expect(&["ur"], PisiOutput::failure(1, "Error: Could not fetch pisi-index.xml.xz\n"));
+    let registry = OperationRegistry::default();
+    let actions = PackageActions::new(&runner, &registry, fixture_db());
+    let mut progress = Vec::new();
+
+    actions.update_repo(|p| progress.push(p)).await.unwrap();
+    assert!(progress.iter().any(|p| p.phase == OperationPhase::Downloading && p.percent == Some(100)));
+
+    let error = actions.update_repo(|_| {}).await.unwrap_err();
+    assert!(matches!(error, PmError::NetworkUnavailable { .. }));
+    assert!(error.is_retryable());
+}
+
+#[tokio::test]
+async fn upgradable_marks_held_packages() {
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["list-upgrades"], PisiOutput::success(fixture("list_upgrades.out")))
+        .expect(&["list-upgrades"], PisiOutput::failure(1, "Error: no repositories\n"));
+    let registry = OperationRegistry::default();
+    let actions = PackageActions::new(&runner, &registry, fixture_db());
+
+    let upgradable = actions.upgradable(&["curl".to_string()]).await.unwrap();
+    let entry = |name: &str, held| UpgradablePackage { name: name.to_string(), held };
+    assert_eq!(upgradable, [entry("bash", false), entry("curl", true), entry("firefox", false)]);
+
+    let error = actions.upgradable(&[]).await.unwrap_err();
+    assert_eq!(error, PmError::IndexMissing { message: "Error: no repositories".to_string() });
+}
+
+#[tokio::test]
+async fn upgrade_all_skips_held_and_isolates_failures() {
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["list-upgrades"], PisiOutput::success(fixture("list_upgrades.out")))
+        .expect(&["up", "-y", "--", "bash", "firefox"], PisiOutput::failure(1, "Error: conflict\n"))
+        .expect(&["up", "-y", "--", "bash"], PisiOutput::success(""))
+        .expect(&["up", "-y", "--", "firefox"], PisiOutput::failure(1, "Error: conflict\n"));
+    let registry = OperationRegistry::default();
+
+    let result = PackageActions::new(&runner, &registry, fixture_db())
+        .upgrade_all(&[], &["curl".to_string()], |_| {})
+        .await
+        .unwrap();
+
+    let statuses: Vec<_> = result.results.iter().map(|r| (r.package.as_str(), r.status)).collect();
+    assert_eq!(statuses, [("bash", PackageResultStatus::Succeeded), ("firefox", PackageResultStatus::Failed)]);
+    assert_eq!(result.results[0].error, None);
+    assert_eq!(result.results[1].error, Some(PmError::DependencyConflict { message: "Error: conflict".to_string() }));
+    assert!(!result.is_success());
+    assert_eq!(runner.calls().len(), 4);
+}
+
+#[tokio::test]
+async fn upgrade_all_skips_upgrades_that_change_held_packages() {
+    // bash'in yeni sürümü sabitli glibc'nin de yükseltilmesini gerektiriyor
+    let index = XmlParser::parse_pisi_index(
+        r#"<PISI>
+            <Package><Name>bash</Name><RuntimeDependencies><Dependency versionFrom="2.39">glibc</Dependency></RuntimeDependencies>
+                <History><Update release="13"><Date>2024-06-01</Date><Version>5.2.37</Version></Update></History></Package>
+            <Package><Name>glibc</Name>
+                <History><Update release="13"><Date>2024-06-01</Date><Version>2.39</Version></Update></History></Package>
+            <Package><Name>firefox</Name><RuntimeDependencies><Dependency>glibc</Dependency></RuntimeDependencies>
+                <History><Update release="1"><Date>2024-06-01</Date><Version>128.0</Version></Update></History></Package>
+        </PISI>"#,
+    )
+    .unwrap();
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["list-upgrades"], PisiOutput::success(fixture("list_upgrades.out")))
+        .expect(&["up", "-y", "--", "firefox"], PisiOutput::success(""));
+    let registry = OperationRegistry::default();
+
+    let result = PackageActions::new(&runner, &registry, fixture_db())
+        .upgrade_all(&index, &["curl".to_string(), "glibc".to_string()], |_| {})
+        .await
+        .unwrap();
+
+    let statuses: Vec<_> = result.results.iter().map(|r| (r.package.as_str(), r.status)).collect();
+    assert_eq!(statuses, [("firefox", PackageResultStatus::Succeeded), ("bash", PackageResultStatus::Skipped)]);
+    assert_eq!(
+        result.results[1].error,
+        Some(PmError::DependencyConflict { message: "Upgrading bash would also change held packages: glibc".to_string() })
+    );
+    assert!(!result.is_success());
+    assert_eq!(calls(&runner), ["list-upgrades", "up -y -- firefox"]);
+}
+
+#[tokio::test]
+async fn hold_and_unhold_round_trip() {
+    let mut settings = AppSettings::default();
+    assert!(settings.hold("curl"));
+    assert!(settings.hold("bash"));
+    assert!(!settings.hold("curl"));
+    assert_eq!(settings.held_packages, ["bash", "curl"]);
+
+    // Ayarlar dosyasına yazılıp okunduğu gibi
+    let saved: AppSettings = serde_json::from_str(&serde_json::to_string(&settings).unwrap()).unwrap();
+    assert!(saved.is_held("curl"));
+
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["list-upgrades"], PisiOutput::success(fixture("list_upgrades.out")))
+        .expect(&["list-upgrades"], PisiOutput::success(fixture("list_upgrades.out")));
+    let registry = OperationRegistry::default();
+    let actions = PackageActions::new(&runner, &registry, fixture_db());
+    let held = |upgradable: Vec<UpgradablePackage>| -> Vec<String> {
+        upgradable.into_iter().filter(|p| p.held).map(|p| p.name).collect()
+    };
+    assert_eq!(held(actions.upgradable(&saved.held_packages).await.unwrap()), ["bash", "curl"]);
+
+    let mut settings = saved;
+    assert!(settings.unhold("curl"));
+    assert!(!settings.unhold("curl"));
+    assert_eq!(held(actions.upgradable(&settings.held_packages).await.unwrap()), ["bash"]);
+}
+
+#[tokio::test]
+async fn transaction_runs_removals_first_in_one_call_per_kind() {
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["rm", "-y", "--", "nano"], PisiOutput::success(""))
+        .expect(&["it", "-y", "--", "vim", "htop"], PisiOutput::success(""));
+    let registry = OperationRegistry::default();
+    let action = |kind, name| TransactionAction { kind, package: package(name) };
+
+    let result = PackageActions::new(&runner, &registry, fixture_db())
+        .run_transaction(&[
+            action(TransactionActionKind::Install, "vim"),
+            action(TransactionActionKind::Remove, "nano"),
+            action(TransactionActionKind::Install, "htop"),
+        ], false, |_| {})
+        .await
+        .unwrap();
+
+    assert!(result.is_success());
+    assert_eq!(calls(&runner), ["rm -y -- nano", "it -y -- vim htop"]);
+}
+
+#[tokio::test]
+async fn cancel_between_groups_skips_the_remaining_groups() {
+    let runner = ScriptedPisiRunner::new().expect(&["rm", "-y", "--", "nano"], PisiOutput::success(""));
+    let registry = OperationRegistry::default();
+    let action = |kind, name| TransactionAction { kind, package: package(name) };
+
+    // İlk grup biter bitmez, ikinci pisi çağrısı başlamadan iptal istenir
+    let result = PackageActions::new(&runner, &registry, fixture_db())
+        .run_transaction(
+            &[action(TransactionActionKind::Remove, "nano"), action(TransactionActionKind::Install, "vim")],
+            true,
+            |progress| {
+                if progress.phase == OperationPhase::Finished {
+                    assert!(registry.request_cancel(&progress.operation_id), "operation is not registered between calls");
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+    let statuses: Vec<_> = result.results.iter().map(|r| (r.package.as_str(), r.status)).collect();
+    assert_eq!(statuses, [("nano", PackageResultStatus::Succeeded), ("vim", PackageResultStatus::Skipped)]);
+    assert!(result.cancelled);
+    assert_eq!(calls(&runner), ["rm -y -- nano"]);
+    assert!(registry.list().is_empty());
+}
+
+#[tokio::test]
+async fn cancel_during_a_failed_batch_skips_the_retries() {
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["up", "-y", "--", "bash", "firefox"], PisiOutput::failure(1, "Error: conflict\n"));
+    let registry = OperationRegistry::default();
+    let action = |kind, name| TransactionAction { kind, package: package(name) };
+
+    let result = PackageActions::new(&runner, &registry, fixture_db())
+        .run_transaction(
+            &[action(TransactionActionKind::Upgrade, "bash"), action(TransactionActionKind::Upgrade, "firefox")],
+            false,
+            |progress| {
+                if progress.phase == OperationPhase::Failed {
+                    registry.request_cancel(&progress.operation_id);
+                }
+            },
+        )
+        .await
+        .unwrap();
+
+    assert!(result.results.iter().all(|r| r.status == PackageResultStatus::Skipped && r.error == Some(PmError::Cancelled)));
+    assert!(result.cancelled);
+    assert_eq!(calls(&runner), ["up -y -- bash firefox"]);
+    assert!(registry.list().is_empty());
+}
+
+#[tokio::test]
+async fn cancel_reaches_an_operation_between_pisi_calls() {
+    let registry = OperationRegistry::default();
+    assert!(registry.begin("transaction-1", "transaction of 2 packages"));
+    assert!(!registry.begin("transaction-1", "pisi it -y -- vim"));
+
+    let (report, cancelled) = tokio::join!(registry.cancel("transaction-1"), async {
+        tokio::task::yield_now().await;
+        registry.finish("transaction-1")
+    });
+    let report = report.unwrap();
+    assert!(cancelled);
+    assert!(report.terminated);
+    assert_eq!(report.phase, OperationPhase::Started);
+
+    let error = registry.cancel("transaction-1").await.unwrap_err();
+    assert!(matches!(error, PmError::InvalidRequest { .. }));
+}
+
+#[tokio::test]
+async fn transaction_rejects_duplicates_and_breaking_removals() {
+    let runner = ScriptedPisiRunner::new();
+    let registry = OperationRegistry::default();
+    let actions = PackageActions::new(&runner, &registry, fixture_db());
+    let action = |kind, name| TransactionAction { kind, package: package(name) };
+
+    let error = actions
+        .run_transaction(&[action(TransactionActionKind::Install, "vim"), action(TransactionActionKind::Remove, "vim")], false, |_| {})
+        .await
+        .unwrap_err();
+    assert_eq!(error, PmError::InvalidRequest {
+        message: "Package vim appears more than once in the transaction".to_string(),
+    });
+
+    // bash ve nano da kaldırıldığı için sadece bunların dışındaki bağımlılar sorun olur
+    let error = actions
+        .run_transaction(&[action(TransactionActionKind::Remove, "glibc"), action(TransactionActionKind::Remove, "nano")], false, |_| {})
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "Removing glibc, nano would break installed packages: bash");
+    assert!(runner.calls().is_empty());
+}
+
+#[tokio::test]
+async fn repository_commands_success_and_failure() {
+    let contrib = RepoName::new("contrib").unwrap();
+    // Eklemeden önce indeks denetlendiği için yerel bir indeks gösterilir
+    let index = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/root/var/lib/pisi/index/pisi-2.0/pisi-index.xml");
+    let url = index.to_str().unwrap();
+    let system = SystemDistribution::default();
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["add-repo", "--", "contrib", url], PisiOutput::success(""))
+        .expect(&["add-repo", "--", "contrib", url], PisiOutput::failure(1, "Error: Repo contrib already present.\n"))
+        .expect(&["enable-repo", "--", "contrib"], PisiOutput::success(""))
+        .expect(&["disable-repo", "--", "contrib"], PisiOutput::success(""))
+        .expect(&["disable-repo", "--", "contrib"], PisiOutput::failure(1, "Error: Repository contrib does not exist.\n"))
+        .expect(&["remove-repo", "--", "contrib"], PisiOutput::success(""))
+        .expect(&["update-repo"], PisiOutput::failure(1, "Error: network unreachable\n"))
+        .expect(&["list-repo"], PisiOutput::failure(1, "Error: database is locked\n"));
+    let manager = RepositoryManager::new(&runner);
+
+    manager.add_repository(&contrib, url, &system).await.unwrap();
+    let error = manager.add_repository(&contrib, url, &system).await.unwrap_err();
+    assert!(error.to_string().contains("Repo contrib already present"));
+    manager.enable_repository(&contrib).await.unwrap();
+    manager.disable_repository(&contrib).await.unwrap();
+    assert!(manager.disable_repository(&contrib).await.is_err());
+    manager.remove_repository(&contrib).await.unwrap();
+    let error = manager.update_repositories(None).await.unwrap_err();
+    assert!(matches!(error, PmError::NetworkUnavailable { .. }));
+    assert_eq!(manager.list_repositories().await.unwrap_err(), PmError::LockHeld);
+}
+
+#[tokio::test]
+async fn list_repositories_reads_local_index() {
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["list-repo"], PisiOutput::success(fixture("list_repo.out")))
+        .expect(&["update-repo", "--", "contrib"], PisiOutput::success(""));
+    let index_dir = fixtures().join("root/var/lib/pisi/index");
+    // Geliştiricinin ayar dosyası okunmaz; anahtarlık ayarlanmadığı için gpgv çağrılmaz
+    let manager = RepositoryManager::from_settings(&runner, &AppSettings::default()).with_index_dir(&index_dir);
+
+    let repos = manager.list_repositories().await.unwrap();
+    assert_eq!(repos.len(), 2);
+    let stable = &repos[0];
+    assert_eq!((stable.name.as_str(), stable.priority, stable.enabled), ("pisi-2.0", 0, true));
+    assert_eq!(stable.url, "https://ciftlik.pisilinux.org/2.0/pisi-index.xml.xz");
+    // İç içe <Conflicts><Package> elemanları sayılmaz
+    assert_eq!(stable.package_count, Some(2));
+    assert!(stable.last_update.is_some());
+    assert_eq!(stable.trust, Some(TrustStatus::ChecksumOnly));
+    assert_eq!(repos[1], RepositoryInfo {
+        name: "contrib".to_string(),
+        url: "https://contrib.pisilinux.org/pisi-index.xml.xz".to_string(),
+        priority: 1,
+        enabled: false,
+        last_update: None,
+        package_count: None,
+        trust: None,
+    });
+
+    manager.update_repositories(Some(&RepoName::new("contrib").unwrap())).await.unwrap();
+
+    if has_gpgv() {
+        let settings = AppSettings {
+            index_keyring: Some(fixtures().join("keys/repository.gpg").display().to_string()),
+            ..AppSettings::default()
+        };
+        let runner = ScriptedPisiRunner::new().expect(&["list-repo"], PisiOutput::success(fixture("list_repo.out")));
+        let manager = RepositoryManager::from_settings(&runner, &settings).with_index_dir(&index_dir);
+        assert_eq!(manager.list_repositories().await.unwrap()[0].trust, Some(TrustStatus::Signed));
+    }
+}
+
+#[tokio::test]
+async fn move_repository_readds_at_new_position() {
+    let before = fixture("list_repo/plain.out");
+    let after = "contrib [inactive]\n   https://contrib.pisilinux.org/pisi-index.xml.xz\n\
+                 pisi-2.0 [active]\n   https://ciftlik.pisilinux.org/2.0/pisi-index.xml.xz\n\
+                 local [active]\n   /var/cache/local-repo/pisi-index.xml\n";
+    let url = "https://contrib.pisilinux.org/pisi-index.xml.xz";
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["list-repo"], PisiOutput::success(before.clone()))
+        .expect(&["remove-repo", "--", "contrib"], PisiOutput::success(""))
+        .expect(&["add-repo", "--no-fetch", "--at", "0", "--", "contrib", url], PisiOutput::success(""))
+        // İndeks tazelenemese de taşıma başarılıdır
+        .expect(&["update-repo", "--", "contrib"], PisiOutput::failure(1, "Error: Could not fetch index: Network is unreachable\n"))
+        .expect(&["disable-repo", "--", "contrib"], PisiOutput::success(""))
+        .expect(&["list-repo"], PisiOutput::success(after))
+        .expect(&["list-repo"], PisiOutput::success(before));
+    let manager = RepositoryManager::new(&runner).with_index_dir("/nonexistent");
+    let contrib = RepoName::new("contrib").unwrap();
+
+    let repos = manager.move_repository(&contrib, MoveDirection::Up).await.unwrap();
+    let order: Vec<_> = repos.iter().map(|r| (r.name.as_str(), r.enabled)).collect();
+    assert_eq!(order, [("contrib", false), ("pisi-2.0", true), ("local", true)]);
+
+    // En sondaki depo daha aşağı taşınamaz; pisi'ye değişiklik komutu gitmez
+    manager.move_repository(&RepoName::new("local").unwrap(), MoveDirection::Down).await.unwrap();
+    assert_eq!(calls(&runner).last().unwrap(), "list-repo");
+    assert_eq!(runner.calls().len(), 7);
+}
+
+#[tokio::test]
+async fn failed_move_restores_original_position() {
+    let url = "https://ciftlik.pisilinux.org/2.0/pisi-index.xml.xz";
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["list-repo"], PisiOutput::success(fixture("list_repo/plain.out")))
+        .expect(&["remove-repo", "--", "pisi-2.0"], PisiOutput::success(""))
+        .expect(&["add-repo", "--no-fetch", "--at", "1", "--", "pisi-2.0", url], PisiOutput::failure(1, "Error: No space left on device\n"))
+        .expect(&["add-repo", "--no-fetch", "--at", "0", "--", "pisi-2.0", url], PisiOutput::success(""))
+        .expect(&["update-repo", "--", "pisi-2.0"], PisiOutput::failure(1, "Error: Could not fetch index: Network is unreachable\n"))
+        .expect(&["list-repo"], PisiOutput::success(fixture("list_repo/plain.out")));
+    let manager = RepositoryManager::new(&runner).with_index_dir("/nonexistent");
+
+    let error = manager.move_repository(&RepoName::new("pisi-2.0").unwrap(), MoveDirection::Down).await.unwrap_err();
+    assert!(matches!(error, PmError::Pisi { .. }), "{:?}", error);
+    assert_eq!(runner.calls().len(), 5);
+
+    let error = manager.move_repository(&RepoName::new("missing").unwrap(), MoveDirection::Up).await.unwrap_err();
+    assert_eq!(error, PmError::InvalidRequest { message: "Repository missing does not exist".to_string() });
+}
+
+#[tokio::test]
+async fn failed_restore_names_the_lost_repository() {
+    let url = "https://ciftlik.pisilinux.org/2.0/pisi-index.xml.xz";
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["list-repo"], PisiOutput::success(fixture("list_repo/plain.out")))
+        .expect(&["remove-repo", "--", "pisi-2.0"], PisiOutput::success(""))
+        .expect(&["add-repo", "--no-fetch", "--at", "1", "--", "pisi-2.0", url], PisiOutput::failure(1, "Error: No space left on device\n"))
+        .expect(&["add-repo", "--no-fetch", "--at", "0", "--", "pisi-2.0", url], PisiOutput::failure(1, "Error: No space left on device\n"));
+    let manager = RepositoryManager::new(&runner).with_index_dir("/nonexistent");
+
+    match manager.move_repository(&RepoName::new("pisi-2.0").unwrap(), MoveDirection::Down).await {
+        Err(PmError::Internal { message }) => {
+            assert!(message.contains("Repository pisi-2.0 was removed"), "{}", message);
+            assert!(message.contains(url), "{}", message);
+        }
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert_eq!(runner.calls().len(), 4);
+}
+
+#[test]
+fn classifies_pisi_failures() {
+    let classify = |code, stderr: &str| PmError::from_pisi_output(&PisiOutput::failure(code, stderr));
+
+    assert_eq!(classify(1, "Error: Another instance of PiSi is running. Try later!\n"), PmError::LockHeld);
+    assert!(matches!(classify(126, "Error executing command as another user: Not authorized\n"), PmError::PermissionDenied { .. }));
+    assert!(matches!(classify(1, "Error: You have to be root for this operation.\n"), PmError::PermissionDenied { .. }));
+    assert!(matches!(classify(1, "Error: Hash mismatch for nano-7.2-1-p2-x86_64.pisi\n"), PmError::ChecksumMismatch { .. }));
+    assert!(matches!(classify(1, "Error: <urlopen error [Errno -3] Temporary failure in name resolution>\n"), PmError::NetworkUnavailable { .. }));
+    assert!(matches!(classify(1, "Error: Package foo not found in any active repository.\n"), PmError::PackageNotFound { .. }));
+    assert!(matches!(classify(1, "Error: Conflicting packages: vim, vim-minimal\n"), PmError::DependencyConflict { .. }));
+    assert!(matches!(classify(1, "Error: No repository found. Please run update-repo.\n"), PmError::IndexMissing { .. }));
+    assert_eq!(classify(2, "Error: something odd\n"), PmError::Pisi { exit_code: 2, message: "Error: something odd".to_string() });
+
+    assert!(classify(1, "Error: database is locked\n").is_retryable());
+    assert!(!classify(1, "Error: Package foo not found in any active repository.\n").is_retryable());
+
+    // Sunucu hatası geçicidir, eksik dosya değildir
+    let server_error = classify(1, "Error: Could not fetch destination file: HTTP Error 503: Service Unavailable\n");
+    assert!(matches!(server_error, PmError::NetworkUnavailable { .. }));
+    assert!(server_error.is_retryable());
+    let not_found = classify(1, "Error: Could not fetch destination file: HTTP Error 404: Not Found\n");
+    assert!(matches!(not_found, PmError::Pisi { .. }), "{:?}", not_found);
+    assert!(!not_found.is_retryable());
+    assert!(classify(1, "Error: <urlopen error timed out>\n").is_retryable());
+
+    // İndirilen paketin bozulması yeniden denenebilir, bozuk yerel veritabanı değil
+    let corrupt_package = classify(1, "Error: Archive nano-7.2-1-p2-x86_64.pisi is corrupt\n");
+    assert!(matches!(corrupt_package, PmError::ChecksumMismatch { .. }));
+    assert!(corrupt_package.is_retryable());
+    for stderr in [
+        "Error: Database is corrupt, please run 'pisi rebuild-db'\n",
+        "Error: Package database corrupted: /var/lib/pisi/package/nano/metadata.xml\n",
+    ] {
+        let error = classify(1, stderr);
+        assert!(matches!(error, PmError::Pisi { .. }), "{:?}", error);
+        assert!(!error.is_retryable(), "{}", stderr);
+    }
+}
+
+#[test]
+fn errors_serialize_with_kind_tag() {
+    let json = serde_json::to_value(PmError::NetworkUnavailable { message: "offline".to_string() }).unwrap();
+    assert_eq!(json, serde_json::json!({ "kind": "NetworkUnavailable", "message": "offline" }));
+    assert_eq!(serde_json::to_value(PmError::Cancelled).unwrap(), serde_json::json!({ "kind": "Cancelled" }));
+}
@@ -0,0 +1,116 @@
+// Her test ikilisi bu modülün sadece bir kısmını kullanır
+#![allow(dead_code)]
+
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Deref;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+
+pub fn fixtures() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures")
+}
+
+/// İmzalı (`.sha1sum` ve `.sig` yanında) `pisi-2.0` depo indeksi
+pub fn fixture_index() -> PathBuf {
+    fixtures().join("root/var/lib/pisi/index/pisi-2.0/pisi-index.xml")
+}
+
+/// İmza doğrulaması `gpgv` gerektirir; kurulu olmayan makinelerde bu denetimler atlanır
+pub fn has_gpgv() -> bool {
+    std::process::Command::new("gpgv").arg("--version").output().is_ok_and(|o| o.status.success())
+}
+
+/// Geçici dizin; test bittiğinde (panikte de) silinir
+pub struct TempDir(PathBuf);
+
+impl TempDir {
+    /// `name` aynı test ikilisinde tekrar kullanılsa da her çağrı ayrı bir dizin alır
+    pub fn new(name: &str) -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let dir = std::env::temp_dir().join(format!(
+            "pm-gui-{}-{}-{}",
+            name,
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::Relaxed)
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+}
+
+impl Deref for TempDir {
+    type Target = Path;
+
+    fn deref(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.0);
+    }
+}
+
+/// Depo sunucusu yerine geçen küçük HTTP sunucusu. Yol başına içerik sunar,
+/// `Range: bytes=0-N` başlığına uyar, bilinmeyen yollara 404 döner.
+pub async fn serve(files: Vec<(&str, Vec<u8>)>) -> String {
+    serve_with_delay(files, Duration::ZERO).await
+}
+
+/// `serve` gibi, ancak yanıt başlıklarından sonra gövdeyi `delay` kadar bekleyip gönderir
+/// (yavaş bir yansıyı taklit eder)
+pub async fn serve_with_delay(files: Vec<(&str, Vec<u8>)>, delay: Duration) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let address = listener.local_addr().unwrap();
+    let files: Arc<HashMap<String, Vec<u8>>> = Arc::new(files.into_iter().map(|(p, b)| (p.to_string(), b)).collect());
+
+    tokio::spawn(async move {
+        loop {
+            let Ok((mut socket, _)) = listener.accept().await else { return };
+            let files = files.clone();
+            tokio::spawn(async move {
+                let mut request = Vec::new();
+                let mut buf = [0u8; 1024];
+                while !request.windows(4).any(|w| w == b"\r\n\r\n") {
+                    match socket.read(&mut buf).await {
+                        Ok(0) | Err(_) => return,
+                        Ok(n) => request.extend_from_slice(&buf[..n]),
+                    }
+                }
+                let request = String::from_utf8_lossy(&request).to_string();
+                let path = request.split_whitespace().nth(1).unwrap_or("/").to_string();
+                let range_end = request
+                    .lines()
+                    .find_map(|l| l.to_ascii_lowercase().strip_prefix("range: bytes=0-").map(str::to_string))
+                    .and_then(|end| end.trim().parse::<usize>().ok());
+
+                let Some(body) = files.get(&path) else {
+                    let _ = socket.write_all(b"HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n").await;
+                    return;
+                };
+                let (status, body) = match range_end {
+                    Some(end) if end + 1 < body.len() => ("206 Partial Content", &body[..=end]),
+                    _ => ("200 OK", &body[..]),
+                };
+                let headers = format!("HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n", status, body.len());
+                if socket.write_all(headers.as_bytes()).await.is_err() {
+                    return;
+                }
+                if !delay.is_zero() {
+                    let _ = socket.flush().await;
+                    tokio::time::sleep(delay).await;
+                }
+                let _ = socket.write_all(body).await;
+            });
+        }
+    });
+
+    format!("http://{}", address)
+}
@@ -0,0 +1,129 @@
+use pm_core::backend::compression::{checked_index_path, open_index_reader, IndexCompression};
+use pm_core::backend::error::PmError;
+use pm_core::backend::xml_parser::XmlParser;
+use std::fs;
+use std::io::{Read, Write};
+use std::path::Path;
+
+mod common;
+use common::{fixture_index, TempDir};
+
+fn xz(data: &[u8]) -> Vec<u8> {
+    let mut encoder = xz2::write::XzEncoder::new(Vec::new(), 6);
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn zst(data: &[u8]) -> Vec<u8> {
+    zstd::encode_all(data, 3).unwrap()
+}
+
+fn gz(data: &[u8]) -> Vec<u8> {
+    let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+    encoder.write_all(data).unwrap();
+    encoder.finish().unwrap()
+}
+
+fn names(path: &Path) -> Vec<String> {
+    XmlParser::load_index_file(path).unwrap().into_iter().map(|p| p.name).collect()
+}
+
+#[test]
+fn detects_format_from_magic_and_extension() {
+    let xml = fs::read(fixture_index()).unwrap();
+    assert_eq!(IndexCompression::from_magic(&xz(&xml)), Some(IndexCompression::Xz));
+    assert_eq!(IndexCompression::from_magic(&zst(&xml)), Some(IndexCompression::Zstd));
+    assert_eq!(IndexCompression::from_magic(&gz(&xml)), Some(IndexCompression::Gzip));
+    assert_eq!(IndexCompression::from_magic(&xml), None);
+    assert_eq!(IndexCompression::from_magic(&[]), None);
+
+    for (name, expected) in [
+        ("pisi-index.xml.xz", IndexCompression::Xz),
+        ("pisi-index.xml.zst", IndexCompression::Zstd),
+        ("pisi-index.xml.gz", IndexCompression::Gzip),
+        ("pisi-index.xml", IndexCompression::None),
+        ("pisi-index", IndexCompression::None),
+    ] {
+        assert_eq!(IndexCompression::from_extension(Path::new(name)), expected, "{}", name);
+    }
+}
+
+#[test]
+fn decodes_every_format() {
+    let dir = TempDir::new("compression");
+    let xml = fs::read(fixture_index()).unwrap();
+    let expected = names(&fixture_index());
+    assert!(!expected.is_empty());
+
+    for (name, bytes) in [
+        ("pisi-index.xml.xz", xz(&xml)),
+        ("pisi-index.xml.zst", zst(&xml)),
+        ("pisi-index.xml.gz", gz(&xml)),
+        ("pisi-index.xml", xml.clone()),
+    ] {
+        let path = dir.join(name);
+        fs::write(&path, bytes).unwrap();
+        assert_eq!(names(&path), expected, "{}", name);
+    }
+}
+
+#[test]
+fn magic_bytes_win_over_a_wrong_extension() {
+    let dir = TempDir::new("compression-magic");
+    let xml = fs::read(fixture_index()).unwrap();
+    let expected = names(&fixture_index());
+
+    // xz içeriği .gz adıyla, zstd içeriği düz .xml adıyla, gzip içeriği .xz adıyla
+    for (name, bytes) in [
+        ("pisi-index.xml.gz", xz(&xml)),
+        ("pisi-index.xml", zst(&xml)),
+        ("pisi-index.xml.xz", gz(&xml)),
+    ] {
+        let path = dir.join(name);
+        fs::write(&path, bytes).unwrap();
+        assert_eq!(names(&path), expected, "{}", name);
+    }
+
+    // Magic byte yoksa uzantıya güvenilir: düz XML .xz adıyla açılınca çözme hatası
+    let path = dir.join("plain.xml.xz");
+    fs::write(&path, &xml).unwrap();
+    assert!(XmlParser::load_index_file(&path).is_err());
+
+    // Okuyucudan açarken de aynı kural
+    let mut decoded = String::new();
+    open_index_reader(&gz(&xml)[..], IndexCompression::None).unwrap().read_to_string(&mut decoded).unwrap();
+    assert_eq!(decoded.as_bytes(), &xml[..]);
+}
+
+#[test]
+fn preview_accepts_only_index_files() {
+    let dir = TempDir::new("compression-preview");
+    let index = dir.join("pisi-index.xml.xz");
+    fs::write(&index, xz(&fs::read(fixture_index()).unwrap())).unwrap();
+    assert_eq!(checked_index_path(&index).unwrap(), fs::canonicalize(&index).unwrap());
+    assert_eq!(checked_index_path(&fixture_index()).unwrap(), fs::canonicalize(fixture_index()).unwrap());
+
+    // İndeks adıyla gizlenmiş başka bir dosyaya sembolik bağ
+    let secret = dir.join("secret.conf");
+    fs::write(&secret, "password=hunter2").unwrap();
+    let link = dir.join("link.xml");
+    std::os::unix::fs::symlink(&secret, &link).unwrap();
+
+    fs::create_dir(dir.join("index.xml")).unwrap();
+
+    for path in [
+        secret.clone(),
+        link,
+        dir.join("index.xml"),
+        dir.join("missing.xml"),
+        "relative/pisi-index.xml".into(),
+        "/etc/passwd".into(),
+        "/proc/self/environ".into(),
+        "/dev/zero".into(),
+    ] {
+        match checked_index_path(&path) {
+            Err(PmError::InvalidRequest { message }) => assert!(message.contains(&path.display().to_string()), "{}", message),
+            other => panic!("{}: unexpected result {:?}", path.display(), other),
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use pm_core::backend::index_cache::{CachedRepository, IndexCache, SourceKey, CACHE_MAGIC, CACHE_SCHEMA_VERSION};
+use pm_core::backend::xml_parser::{PackageInfo, RepositoryIndex, XmlParser};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::time::{Duration, SystemTime};
+
+mod common;
+use common::{fixture_index, TempDir};
+
+fn packages(repo: &str) -> Vec<PackageInfo> {
+    XmlParser::load_repository(&RepositoryIndex { name: repo.to_string(), path: fixture_index(), priority: 0 }).unwrap()
+}
+
+fn cached(name: &str, hash: u64) -> CachedRepository {
+    let key = SourceKey { hash, ..SourceKey::of_file(&fixture_index()).unwrap() };
+    CachedRepository { name: name.to_string(), key, packages: packages(name) }
+}
+
+fn wanted(repos: &[&CachedRepository]) -> Vec<(String, SourceKey)> {
+    repos.iter().map(|r| (r.name.clone(), r.key)).collect()
+}
+
+#[test]
+fn source_key_ignores_timestamps_but_not_content() {
+    let dir = TempDir::new("cache-key");
+    let index = dir.join("pisi-index.xml");
+    fs::copy(fixture_index(), &index).unwrap();
+    let key = SourceKey::of_file(&index).unwrap();
+    assert_eq!(key.size, fs::metadata(&index).unwrap().len());
+
+    // Saat kayması veya yedekten geri yükleme: zaman damgası geriye gider, içerik aynı
+    let file = fs::File::options().write(true).open(&index).unwrap();
+    file.set_modified(SystemTime::UNIX_EPOCH + Duration::from_secs(86_400)).unwrap();
+    assert_eq!(SourceKey::of_file(&index).unwrap(), key);
+
+    // Aynı boyutta farklı içerik (korunan zaman damgasıyla yeniden yazılmış indeks)
+    let content = fs::read_to_string(&index).unwrap().replace("nano", "nan0");
+    fs::write(&index, content).unwrap();
+    let changed = SourceKey::of_file(&index).unwrap();
+    assert_eq!(changed.size, key.size);
+    assert_ne!(changed.hash, key.hash);
+}
+
+#[test]
+fn round_trips_with_private_permissions() {
+    let dir = TempDir::new("cache-roundtrip");
+    let cache = IndexCache::new(dir.join("nested/index-cache.bin"));
+    let (stable, contrib) = (cached("pisi-2.0", 1), cached("contrib", 2));
+
+    cache.save(&[stable.clone(), contrib.clone()]).unwrap();
+    let bytes = fs::read(cache.path()).unwrap();
+    assert_eq!(bytes[..8], CACHE_MAGIC);
+    assert_eq!(bytes[8..12], CACHE_SCHEMA_VERSION.to_le_bytes());
+    assert_eq!(fs::metadata(cache.path()).unwrap().permissions().mode() & 0o777, 0o600);
+    assert_eq!(fs::metadata(cache.path().parent().unwrap()).unwrap().permissions().mode() & 0o777, 0o700);
+    // Geçici dosya geride kalmaz
+    assert_eq!(fs::read_dir(cache.path().parent().unwrap()).unwrap().count(), 1);
+
+    let loaded = cache.load(&wanted(&[&stable, &contrib]));
+    assert_eq!(loaded.len(), 2);
+    let names: Vec<_> = loaded["contrib"].iter().map(|p| (p.name.as_str(), p.repository.as_str())).collect();
+    assert_eq!(names, stable.packages.iter().map(|p| (p.name.as_str(), "contrib")).collect::<Vec<_>>());
+}
+
+#[test]
+fn invalidates_only_changed_repositories() {
+    let dir = TempDir::new("cache-per-repo");
+    let cache = IndexCache::new(dir.join("index-cache.bin"));
+    let (stable, contrib) = (cached("pisi-2.0", 1), cached("contrib", 2));
+    cache.save(&[stable.clone(), contrib.clone()]).unwrap();
+
+    let mut refreshed = contrib.clone();
+    refreshed.key.hash = 3;
+    let loaded = cache.load(&wanted(&[&stable, &refreshed]));
+    assert_eq!(loaded.keys().collect::<Vec<_>>(), ["pisi-2.0"]);
+    // Eskiyen bir depo dosyanın tamamını geçersiz kılmaz
+    assert!(cache.path().exists());
+
+    let mut resized = stable.clone();
+    resized.key.size += 1;
+    assert!(cache.load(&wanted(&[&resized])).is_empty());
+    // Önbellekte olmayan depo
+    assert!(cache.load(&wanted(&[&cached("local", 1)])).is_empty());
+}
+
+#[test]
+fn discards_cache_on_any_header_mismatch() {
+    let dir = TempDir::new("cache-mismatch");
+    let cache = IndexCache::new(dir.join("index-cache.bin"));
+    let stable = cached("pisi-2.0", 1);
+    let keys = wanted(&[&stable]);
+
+    let rewrite = |edit: &dyn Fn(&mut Vec<u8>)| {
+        cache.save(std::slice::from_ref(&stable)).unwrap();
+        let mut bytes = fs::read(cache.path()).unwrap();
+        edit(&mut bytes);
+        fs::write(cache.path(), bytes).unwrap();
+    };
+
+    // Sihirli sayı, şema sürümü ve başlık uzunluğu
+    for offset in [0, 8, 15] {
+        rewrite(&|bytes| bytes[offset] ^= 0xff);
+        assert!(cache.load(&keys).is_empty(), "offset {} was accepted", offset);
+        assert!(!cache.path().exists(), "offset {} was not discarded", offset);
+    }
+
+    // Kesik paket listesi
+    rewrite(&|bytes| bytes.truncate(bytes.len() - 1));
+    assert!(cache.load(&keys).is_empty());
+    assert!(!cache.path().exists());
+
+    // Eski, başlıksız önbellek biçimi
+    fs::write(cache.path(), bincode::serialize(&stable.packages).unwrap()).unwrap();
+    assert!(cache.load(&keys).is_empty());
+    assert!(!cache.path().exists());
+}
+
+#[test]
+fn ignores_cache_writable_by_others() {
+    let dir = TempDir::new("cache-perms");
+    let cache = IndexCache::new(dir.join("index-cache.bin"));
+    let stable = cached("pisi-2.0", 1);
+
+    cache.save(std::slice::from_ref(&stable)).unwrap();
+    fs::set_permissions(cache.path(), fs::Permissions::from_mode(0o666)).unwrap();
+    assert!(cache.load(&wanted(&[&stable])).is_empty());
+    assert!(!cache.path().exists());
+}
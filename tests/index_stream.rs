@@ -0,0 +1,55 @@
+use pm_core::backend::index_stream::PackageStream;
+use pm_core::backend::xml_parser::XmlParser;
+use std::path::PathBuf;
+
+fn fixture(path: &str) -> String {
+    std::fs::read_to_string(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(path)).unwrap()
+}
+
+/// Akış parser'ı ile tek DOM'lu (roxmltree) parser aynı paketleri üretmeli
+#[test]
+fn stream_matches_dom_parser() {
+    for path in ["index/pisi-index.xml", "root/var/lib/pisi/index/pisi-2.0/pisi-index.xml"] {
+        let xml = fixture(path);
+        let dom = XmlParser::parse_pisi_index(&xml).unwrap();
+        let stream = PackageStream::new(xml.as_bytes()).collect::<anyhow::Result<Vec<_>>>().unwrap();
+
+        assert!(!dom.is_empty(), "{}", path);
+        assert_eq!(dom.len(), stream.len(), "{}", path);
+        for (dom, stream) in dom.iter().zip(&stream) {
+            assert_eq!(dom.name, stream.name, "{}", path);
+            assert_eq!((&dom.version, dom.release), (&stream.version, stream.release), "{}", dom.name);
+            // Alanların tamamı (geçmiş, bağımlılıklar, çakışmalar, sağlananlar dahil)
+            assert_eq!(serde_json::to_value(dom).unwrap(), serde_json::to_value(stream).unwrap(), "{}", dom.name);
+        }
+    }
+}
+
+#[test]
+fn parses_the_full_fixture_package() {
+    let packages = XmlParser::parse_pisi_index_reader(fixture("index/pisi-index.xml").as_bytes()).unwrap();
+    let names: Vec<_> = packages.iter().map(|p| p.name.as_str()).collect();
+    // Geçmişi olmayan paket atlanır; <Conflicts>/<Obsoletes> içindeki <Package>'lar paket sayılmaz
+    assert_eq!(names, ["nano", "nano-docs"]);
+
+    let nano = &packages[0];
+    assert_eq!((nano.version.as_str(), nano.release), ("7.2", 3));
+    assert_eq!(nano.summary, "Küçük, kullanışlı metin düzenleyici");
+    let history: Vec<_> = nano.history.iter().map(|h| (h.version.as_str(), h.release, h.date.as_str())).collect();
+    assert_eq!(history, [("7.2", 3, "2024-01-10"), ("7.2", 2, "2023-06-01"), ("6.0", 1, "2022-01-01")]);
+    let dependencies: Vec<_> = nano.dependencies.iter().map(|d| d.name.as_str()).collect();
+    // <AnyDependency> içindeki alternatifler doğrudan bağımlılık değildir
+    assert_eq!(dependencies, ["ncurses", "glibc"]);
+    assert_eq!(nano.dependencies[0].constraint.version_from.as_deref(), Some("6.2"));
+    assert_eq!(nano.dependencies[1].constraint.release_from, Some(4));
+    assert_eq!(nano.dependencies[1].constraint.version_to.as_deref(), Some("2.40"));
+    let conflicts: Vec<_> = nano.conflicts.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(conflicts, ["nano-tiny", "pico"]);
+    assert_eq!(nano.replaces[0].name, "nano-legacy");
+    // Derleme bağımlılıkları <Source> altından okunur
+    let build: Vec<_> = nano.build_dependencies.iter().map(|d| d.name.as_str()).collect();
+    assert_eq!(build, ["ncurses-devel", "file-devel"]);
+    assert_eq!(nano.build_dependencies[0].constraint.version_from.as_deref(), Some("6.2"));
+    assert!(packages[1].build_dependencies.is_empty());
+    assert_eq!(nano.package_size, 612_345);
+}
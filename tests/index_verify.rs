@@ -0,0 +1,124 @@
+use pm_core::backend::error::PmError;
+use pm_core::backend::index_verify::{sha1_file, IndexVerifier, TrustStatus};
+use pm_core::backend::index_cache::{IndexCache, SourceKey};
+use pm_core::backend::xml_parser::{RepositoryIndex, XmlParser};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+mod common;
+use common::{fixture_index, fixtures, TempDir};
+
+fn keyring(name: &str) -> IndexVerifier {
+    IndexVerifier::new(Some(fixtures().join("keys").join(name)))
+}
+
+/// İmzalı fixture indeksini ve yan dosyalarını geçici bir dizine kopyalar
+fn copy_index(name: &str, sidecars: &[&str]) -> (TempDir, PathBuf) {
+    let dir = TempDir::new(&format!("verify-{}", name));
+    let index = dir.join("pisi-index.xml");
+    fs::copy(fixture_index(), &index).unwrap();
+    for suffix in sidecars {
+        fs::copy(sidecar(&fixture_index(), suffix), sidecar(&index, suffix)).unwrap();
+    }
+    (dir, index)
+}
+
+fn sidecar(index: &Path, suffix: &str) -> PathBuf {
+    PathBuf::from(format!("{}{}", index.display(), suffix))
+}
+
+#[test]
+fn verifies_checksum_and_signature() {
+    let index = fixture_index();
+    assert_eq!(sha1_file(&index).unwrap(), "81f5641d162ef2e12f835dd4f3d7496f1bf7ccd8");
+
+    assert_eq!(keyring("repository.gpg").verify(&index), TrustStatus::Signed);
+    // Anahtarlık ayarlanmamışsa imza denetlenmez
+    assert_eq!(IndexVerifier::default().verify(&index), TrustStatus::ChecksumOnly);
+}
+
+#[test]
+fn rejects_signature_from_unknown_key() {
+    let status = keyring("other.gpg").verify(&fixture_index());
+    match &status {
+        TrustStatus::BadSignature { message } => assert!(message.contains("No public key"), "{}", message),
+        other => panic!("unexpected status {:?}", other),
+    }
+    assert!(status.is_rejected());
+}
+
+#[test]
+fn detects_tampered_index() {
+    let (_dir, index) = copy_index("tampered", &[".sha1sum", ".sig"]);
+    let mut content = fs::read_to_string(&index).unwrap();
+    content = content.replace("nano", "nan0");
+    fs::write(&index, content).unwrap();
+
+    let status = keyring("repository.gpg").verify(&index);
+    assert!(matches!(
+        &status,
+        TrustStatus::ChecksumMismatch { expected, .. } if expected == "81f5641d162ef2e12f835dd4f3d7496f1bf7ccd8"
+    ), "{:?}", status);
+    assert!(status.is_rejected());
+
+    // Checksum dosyası da güncellenmişse imza yine de tutmaz
+    fs::write(sidecar(&index, ".sha1sum"), sha1_file(&index).unwrap()).unwrap();
+    assert!(matches!(keyring("repository.gpg").verify(&index), TrustStatus::BadSignature { .. }));
+}
+
+#[test]
+fn index_without_sidecars_is_unverified() {
+    let (_dir, index) = copy_index("bare", &[]);
+    let status = IndexVerifier::default().verify(&index);
+    assert_eq!(status, TrustStatus::Unverified);
+    assert!(!status.is_rejected());
+}
+
+#[test]
+fn missing_signature_is_rejected_when_a_keyring_is_set() {
+    for (name, sidecars) in [("unsigned", &[".sha1sum"][..]), ("unsigned-bare", &[][..])] {
+        let (_dir, index) = copy_index(name, sidecars);
+        let status = keyring("repository.gpg").verify(&index);
+        assert_eq!(status, TrustStatus::MissingSignature, "{}", name);
+        assert!(status.is_rejected());
+    }
+}
+
+#[test]
+fn rejected_repository_is_skipped_and_others_are_served() {
+    let (dir, index) = copy_index("repos", &[".sha1sum"]);
+    fs::write(sidecar(&index, ".sha1sum"), "0000000000000000000000000000000000000000  pisi-index.xml\n").unwrap();
+    let repos = [
+        RepositoryIndex { name: "broken".to_string(), path: index, priority: 0 },
+        RepositoryIndex { name: "pisi-2.0".to_string(), path: fixture_index(), priority: 1 },
+    ];
+
+    let verifier = keyring("repository.gpg");
+    match verifier.check_repository(&repos[0]) {
+        Err(PmError::ChecksumMismatch { message }) => assert!(message.contains("broken"), "{}", message),
+        other => panic!("unexpected result {:?}", other),
+    }
+    assert!(verifier.check_repository(&repos[1]).is_ok());
+
+    let cache = IndexCache::new(dir.join("cache/index-cache.bin"));
+    let packages = XmlParser::load_verified_repositories(&repos, &verifier, Some(&cache)).unwrap();
+    assert!(!packages.is_empty());
+    assert!(packages.iter().all(|p| p.repository == "pisi-2.0"));
+
+    // Reddedilen depo önbelleğe yazılmaz; bir sonraki yüklemede yeniden doğrulanır
+    let keys: Vec<_> = repos.iter().map(|r| (r.name.clone(), SourceKey::of_file(&r.path).unwrap())).collect();
+    assert_eq!(cache.load(&keys).keys().collect::<Vec<_>>(), ["pisi-2.0"]);
+
+    // Kullanılabilir depo kalmazsa doğrulama hatası döner
+    match XmlParser::load_verified_repositories(&repos[..1], &verifier, None) {
+        Err(e) => assert!(matches!(e.downcast_ref::<PmError>(), Some(PmError::ChecksumMismatch { .. })), "{}", e),
+        Ok(packages) => panic!("unexpected packages {:?}", packages),
+    }
+}
+
+#[test]
+fn trust_status_serializes_with_status_tag() {
+    let json = serde_json::to_value(TrustStatus::ChecksumMismatch { expected: "a".to_string(), found: "b".to_string() }).unwrap();
+    assert_eq!(json, serde_json::json!({ "status": "checksum_mismatch", "expected": "a", "found": "b" }));
+    assert_eq!(serde_json::to_value(TrustStatus::Signed).unwrap(), serde_json::json!({ "status": "signed" }));
+}
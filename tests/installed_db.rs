@@ -0,0 +1,71 @@
+use pm_core::backend::installed_db::{InstallReason, InstalledDb};
+use std::path::PathBuf;
+
+fn fixture_db() -> InstalledDb {
+    InstalledDb::new(PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/root"))
+}
+
+#[test]
+fn lists_installed_packages_with_versions() {
+    let packages = fixture_db().list().unwrap();
+    let summary: Vec<_> = packages
+        .iter()
+        .map(|p| (p.name.as_str(), p.version.as_str(), p.release))
+        .collect();
+
+    // Yarım kalmış (metadata.xml olmayan) dizinler atlanır
+    assert_eq!(summary, [("bash", "5.2_p15", 4), ("glibc", "2.35", 12), ("nano", "7.2", 2)]);
+    assert!(packages.iter().all(|p| p.install_date.is_some()));
+}
+
+#[test]
+fn names_match_list() {
+    let db = fixture_db();
+    let from_list: Vec<_> = db.list().unwrap().into_iter().map(|p| p.name).collect();
+    assert_eq!(db.names().unwrap(), from_list);
+    assert!(db.is_installed("nano"));
+    assert!(!db.is_installed("broken-leftover"));
+}
+
+#[test]
+fn infers_install_reason_from_installed_dependencies() {
+    let db = fixture_db();
+    assert_eq!(db.get("glibc").unwrap().unwrap().reason, InstallReason::Dependency);
+    assert_eq!(db.get("nano").unwrap().unwrap().reason, InstallReason::Explicit);
+    assert_eq!(db.get("bash").unwrap().unwrap().reason, InstallReason::Explicit);
+    assert!(db.get("firefox").unwrap().is_none());
+}
+
+#[test]
+fn installed_size_falls_back_to_files_xml() {
+    let db = fixture_db();
+    assert_eq!(db.get("glibc").unwrap().unwrap().installed_size, 14680064);
+    // bash'in metadata.xml'inde <InstalledSize> yok
+    assert_eq!(db.get("bash").unwrap().unwrap().installed_size, 1234);
+}
+
+#[test]
+fn keeps_dependency_constraints() {
+    let bash = fixture_db().get("bash").unwrap().unwrap();
+    let glibc = &bash.dependencies[0];
+    assert_eq!(glibc.name, "glibc");
+    assert!(glibc.is_satisfied_by("2.35", 12));
+    assert!(!glibc.is_satisfied_by("2.41", 1));
+}
+
+#[test]
+fn reads_file_list() {
+    let files = fixture_db().files("nano").unwrap();
+    assert_eq!(files.len(), 2);
+    assert_eq!(files[0].path, "usr/bin/nano");
+    assert_eq!(files[0].file_type, "executable");
+    assert_eq!(files[0].size, 1000);
+    assert_eq!(files[1].hash.as_deref(), Some("89e6c98d92887913cadf06b2adb97f26cde4849b"));
+}
+
+#[test]
+fn missing_database_is_an_error() {
+    let db = InstalledDb::new("/nonexistent/pm-gui-test-root");
+    assert!(db.list().is_err());
+    assert!(db.names().is_err());
+}
@@ -0,0 +1,53 @@
+use pm_core::backend::repository::{parse_list_repo, RepositoryInfo};
+use std::fs;
+use std::path::PathBuf;
+
+fn fixture_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/pisi/list_repo")
+}
+
+/// `<ad>.out` çıktısını ayrıştırıp `<ad>.json` beklenen sonucuyla karşılaştırır
+fn assert_golden(name: &str) {
+    let dir = fixture_dir();
+    let output = fs::read_to_string(dir.join(format!("{}.out", name))).unwrap();
+    let expected: Vec<RepositoryInfo> =
+        serde_json::from_str(&fs::read_to_string(dir.join(format!("{}.json", name))).unwrap()).unwrap();
+
+    assert_eq!(parse_list_repo(&output), expected, "golden mismatch for {}", name);
+}
+
+#[test]
+fn parses_plain_output() {
+    assert_golden("plain");
+}
+
+#[test]
+fn parses_coloured_output() {
+    assert_golden("color");
+}
+
+#[test]
+fn parses_turkish_output_and_skips_warnings() {
+    assert_golden("tr");
+}
+
+#[test]
+fn parses_empty_output() {
+    assert_golden("empty");
+}
+
+#[test]
+fn every_capture_has_a_golden_file() {
+    for entry in fs::read_dir(fixture_dir()).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|e| e == "out") {
+            assert!(path.with_extension("json").is_file(), "{} has no golden file", path.display());
+        }
+    }
+}
+
+#[test]
+fn ignores_headers_without_a_known_status() {
+    let repos = parse_list_repo("Repositories:\nfoo [unknown]\n   https://example.org/pisi-index.xml\n");
+    assert!(repos.is_empty());
+}
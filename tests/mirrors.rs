@@ -0,0 +1,215 @@
+use pm_core::backend::error::PmError;
+use pm_core::backend::mirrors::{measure_mirrors, mirror_report, parse_mirror_list, rank, Mirror, MirrorReport, MirrorResult, MIRROR_CACHE_MAX_AGE, MIRROR_SAMPLE_BYTES};
+use pm_core::backend::names::RepoName;
+use pm_core::backend::repo_probe::SystemDistribution;
+use pm_core::backend::repository::RepositoryManager;
+use pm_core::backend::runner::{PisiOutput, ScriptedPisiRunner};
+use std::time::Duration;
+
+mod common;
+use common::{fixture_index, serve, serve_with_delay, TempDir};
+
+fn mirror(url: &str) -> Mirror {
+    Mirror { repository: "pisi-2.0".to_string(), url: url.to_string(), location: None }
+}
+
+fn result(url: &str, latency_ms: Option<u64>, throughput_bps: Option<u64>) -> MirrorResult {
+    MirrorResult {
+        mirror: mirror(url),
+        latency_ms,
+        throughput_bps,
+        error: throughput_bps.is_none().then(|| PmError::NetworkUnavailable { message: "down".to_string() }),
+    }
+}
+
+#[test]
+fn parses_mirror_list_with_locations() {
+    let mirrors = parse_mirror_list(
+        "# depo adres konum\n\npisi-2.0  https://a.example.org/2.0/pisi-index.xml.xz   Avrupa/Almanya\npisi-2.0\thttp://b.example.org/pisi-index.xml\n",
+    ).unwrap();
+    assert_eq!(
+        mirrors,
+        [
+            Mirror {
+                repository: "pisi-2.0".to_string(),
+                url: "https://a.example.org/2.0/pisi-index.xml.xz".to_string(),
+                location: Some("Avrupa/Almanya".to_string()),
+            },
+            mirror("http://b.example.org/pisi-index.xml"),
+        ]
+    );
+
+    for invalid in [
+        "pisi-2.0 /srv/repo/pisi-index.xml",
+        "pisi-2.0 https://a.example.org/2.0/",
+        // Depo sütunu olmayan eski biçim
+        "https://a.example.org/2.0/pisi-index.xml.xz",
+        "-rf https://a.example.org/2.0/pisi-index.xml.xz",
+    ] {
+        assert!(matches!(parse_mirror_list(invalid), Err(PmError::InvalidRequest { .. })), "{} was accepted", invalid);
+    }
+}
+
+#[test]
+fn ranks_reachable_mirrors_by_throughput_then_latency() {
+    let mut results = vec![
+        result("http://down/pisi-index.xml", None, None),
+        result("http://slow/pisi-index.xml", Some(10), Some(1_000)),
+        result("http://fast-far/pisi-index.xml", Some(200), Some(50_000)),
+        result("http://fast-near/pisi-index.xml", Some(20), Some(50_000)),
+    ];
+    rank(&mut results);
+
+    let order: Vec<_> = results.iter().map(|r| r.mirror.url.as_str()).collect();
+    assert_eq!(
+        order,
+        ["http://fast-near/pisi-index.xml", "http://fast-far/pisi-index.xml", "http://slow/pisi-index.xml", "http://down/pisi-index.xml"]
+    );
+
+    let report = MirrorReport { tested_at: 0, results };
+    assert_eq!(report.fastest().unwrap().url, "http://fast-near/pisi-index.xml");
+    let unreachable = MirrorReport { tested_at: 0, results: vec![result("http://down/pisi-index.xml", None, None)] };
+    assert_eq!(unreachable.fastest(), None);
+}
+
+#[test]
+fn fastest_mirror_is_chosen_per_repository() {
+    let mut contrib = result("http://contrib-mirror/pisi-index.xml", Some(5), Some(90_000));
+    contrib.mirror.repository = "contrib".to_string();
+    let mut results = vec![
+        contrib,
+        result("http://stable-mirror/pisi-index.xml", Some(20), Some(10_000)),
+        result("http://stable-down/pisi-index.xml", None, None),
+    ];
+    rank(&mut results);
+    let report = MirrorReport { tested_at: 0, results };
+
+    assert_eq!(report.fastest_for("pisi-2.0").unwrap().url, "http://stable-mirror/pisi-index.xml");
+    assert_eq!(report.fastest_for("contrib").unwrap().url, "http://contrib-mirror/pisi-index.xml");
+    assert_eq!(report.fastest_for("local"), None);
+}
+
+#[tokio::test]
+async fn measures_mirrors_and_reports_unreachable_ones() {
+    // Örnek boyutundan büyük bir indeks: Range başlığı sayesinde sadece örnek kadarı indirilir
+    let index = vec![b'x'; MIRROR_SAMPLE_BYTES * 2];
+    let fast = serve(vec![("/pisi-index.xml", index.clone())]).await;
+    let slow = serve_with_delay(vec![("/pisi-index.xml", index)], Duration::from_millis(300)).await;
+
+    let mirrors = [
+        mirror(&format!("{}/pisi-index.xml", slow)),
+        mirror(&format!("{}/missing/pisi-index.xml", fast)),
+        mirror(&format!("{}/pisi-index.xml", fast)),
+    ];
+    let report = measure_mirrors(&mirrors).await.unwrap();
+
+    let order: Vec<_> = report.results.iter().map(|r| r.mirror.clone()).collect();
+    assert_eq!(order, [mirrors[2].clone(), mirrors[0].clone(), mirrors[1].clone()]);
+    assert!(report.results[0].throughput_bps > report.results[1].throughput_bps);
+    match &report.results[2].error {
+        Some(PmError::NetworkUnavailable { message }) => assert!(message.contains("404"), "{}", message),
+        other => panic!("unexpected error {:?}", other),
+    }
+    assert_eq!(report.fastest(), Some(&mirrors[2]));
+}
+
+#[tokio::test]
+async fn reuses_fresh_report_until_list_changes_or_forced() {
+    let dir = TempDir::new("mirrors-cache");
+    let base = serve(vec![("/pisi-index.xml", vec![b'x'; 1024])]).await;
+    let list = dir.join("mirrors.list");
+    let cache = dir.join("cache/mirrors.json");
+    std::fs::write(&list, format!("pisi-2.0 {}/pisi-index.xml  Yerel\n", base)).unwrap();
+
+    let first = mirror_report(&list, Some(&cache), false).await.unwrap();
+    assert!(first.fastest().is_some());
+    assert_eq!(MirrorReport::load(&cache), Some(first.clone()));
+
+    // Kayıtlı rapor tazeyken yeniden ölçülmez
+    let mut stale = first.clone();
+    stale.results[0].latency_ms = Some(123_456);
+    stale.save(&cache).unwrap();
+    assert_eq!(mirror_report(&list, Some(&cache), false).await.unwrap(), stale);
+    assert_ne!(mirror_report(&list, Some(&cache), true).await.unwrap().results[0].latency_ms, Some(123_456));
+
+    // Eski rapor veya değişmiş liste yeniden ölçüm gerektirir
+    let mirrors = parse_mirror_list(&std::fs::read_to_string(&list).unwrap()).unwrap();
+    let old = MirrorReport { tested_at: first.tested_at - MIRROR_CACHE_MAX_AGE.as_secs() - 1, ..first.clone() };
+    assert!(first.is_fresh(&mirrors, MIRROR_CACHE_MAX_AGE));
+    assert!(!old.is_fresh(&mirrors, MIRROR_CACHE_MAX_AGE));
+    assert!(!first.is_fresh(&[mirror("https://other.example.org/pisi-index.xml")], MIRROR_CACHE_MAX_AGE));
+
+    // Bozuk kayıt yok sayılır
+    std::fs::write(&cache, "{").unwrap();
+    assert_eq!(MirrorReport::load(&cache), None);
+}
+
+fn pisilinux_2_0() -> SystemDistribution {
+    SystemDistribution {
+        distribution: Some("PisiLinux".to_string()),
+        release: Some("2.0".to_string()),
+        architecture: Some("x86_64".to_string()),
+    }
+}
+
+fn list_repo(url: &str) -> String {
+    format!(
+        "pisi-2.0 [active]\n   {}\ncontrib [inactive]\n   https://contrib.pisilinux.org/pisi-index.xml.xz\n\
+         local [active]\n   /var/cache/local-repo/pisi-index.xml\n",
+        url
+    )
+}
+
+#[tokio::test]
+async fn switch_mirror_keeps_position_and_state() {
+    let old_url = "https://ciftlik.pisilinux.org/2.0/pisi-index.xml.xz";
+    let base = serve(vec![("/2.0/pisi-index.xml", std::fs::read(fixture_index()).unwrap())]).await;
+    let new_url = format!("{}/2.0/pisi-index.xml", base);
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["list-repo"], PisiOutput::success(list_repo(old_url)))
+        .expect(&["remove-repo", "--", "pisi-2.0"], PisiOutput::success(""))
+        .expect(&["add-repo", "--at", "0", "--", "pisi-2.0", &new_url], PisiOutput::success(""))
+        .expect(&["list-repo"], PisiOutput::success(list_repo(&new_url)))
+        .expect(&["list-repo"], PisiOutput::success(list_repo(&new_url)));
+    let manager = RepositoryManager::new(&runner).with_index_dir("/nonexistent");
+    let name = RepoName::new("pisi-2.0").unwrap();
+
+    let repos = manager.switch_mirror(&name, &new_url, &pisilinux_2_0()).await.unwrap();
+    assert_eq!((repos[0].name.as_str(), repos[0].url.as_str(), repos[0].enabled), ("pisi-2.0", new_url.as_str(), true));
+
+    // Aynı adrese geçiş pisi'ye değişiklik komutu göndermez
+    manager.switch_mirror(&name, &new_url, &pisilinux_2_0()).await.unwrap();
+    assert_eq!(runner.calls().len(), 5);
+
+    let error = manager.switch_mirror(&name, "ftp://mirror.example.org/pisi-index.xml", &pisilinux_2_0()).await.unwrap_err();
+    assert!(matches!(error, PmError::InvalidRequest { .. }));
+}
+
+#[tokio::test]
+async fn switch_mirror_probes_the_new_index_before_removing_the_old_entry() {
+    let old_url = "https://ciftlik.pisilinux.org/2.0/pisi-index.xml.xz";
+    let base = serve(vec![("/2.0/pisi-index.xml", std::fs::read(fixture_index()).unwrap())]).await;
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["list-repo"], PisiOutput::success(list_repo(old_url)))
+        .expect(&["list-repo"], PisiOutput::success(list_repo(old_url)));
+    let manager = RepositoryManager::new(&runner).with_index_dir("/nonexistent");
+    let name = RepoName::new("pisi-2.0").unwrap();
+
+    // Bulunmayan indeks
+    let error = manager.switch_mirror(&name, &format!("{}/missing/pisi-index.xml", base), &pisilinux_2_0()).await.unwrap_err();
+    assert!(matches!(error, PmError::InvalidRequest { .. }), "{:?}", error);
+
+    // Başka bir sürümün yansısı
+    let system = SystemDistribution { release: Some("2.1".to_string()), ..pisilinux_2_0() };
+    match manager.switch_mirror(&name, &format!("{}/2.0/pisi-index.xml", base), &system).await {
+        Err(PmError::InvalidRequest { message }) => assert!(message.contains("release 2.0 (expected 2.1)"), "{}", message),
+        other => panic!("unexpected result {:?}", other),
+    }
+
+    // Sadece listeleme yapıldı; depo kaldırılmadı
+    assert!(calls_are_list_only(&runner));
+}
+
+fn calls_are_list_only(runner: &ScriptedPisiRunner) -> bool {
+    runner.calls().iter().all(|c| c == &["list-repo"])
+}
@@ -0,0 +1,60 @@
+use pm_core::backend::names::{NameError, NameKind, PackageName, RepoName};
+use pm_core::backend::transaction::TransactionAction;
+
+#[test]
+fn rejects_unsafe_names() {
+    use NameError::*;
+    let invalid_start = |name: &str| InvalidStart { kind: NameKind::Package, name: name.to_string() };
+    let invalid_char = |name: &str, character| InvalidCharacter { kind: NameKind::Package, name: name.to_string(), character };
+    let long = "a".repeat(256);
+
+    let cases: &[(&str, NameError)] = &[
+        ("", Empty { kind: NameKind::Package }),
+        ("-rf", invalid_start("-rf")),
+        ("--ignore-dependency", invalid_start("--ignore-dependency")),
+        (" nano", invalid_start(" nano")),
+        ("nano vim", invalid_char("nano vim", ' ')),
+        ("nano\t", invalid_char("nano\t", '\t')),
+        ("/etc/passwd", invalid_start("/etc/passwd")),
+        ("../nano", invalid_start("../nano")),
+        ("nano/../../etc", invalid_char("nano/../../etc", '/')),
+        ("nano\n--ignore-safety", invalid_char("nano\n--ignore-safety", '\n')),
+        ("nano\0", invalid_char("nano\0", '\0')),
+        ("nano\x1b[2J", invalid_char("nano\x1b[2J", '\x1b')),
+        ("nano;rm", invalid_char("nano;rm", ';')),
+        ("nanö", invalid_char("nanö", 'ö')),
+        (&long, TooLong { kind: NameKind::Package, length: 256 }),
+    ];
+
+    for (name, expected) in cases {
+        assert_eq!(PackageName::new(*name).as_ref(), Err(expected), "{:?}", name);
+        assert!(name.parse::<PackageName>().is_err(), "{:?}", name);
+        // Tauri komut parametreleri serde ile okunur; `try_from` geçersiz adı reddetmeli
+        let json = serde_json::to_string(name).unwrap();
+        let error = serde_json::from_str::<PackageName>(&json).unwrap_err();
+        assert!(error.to_string().contains(&expected.to_string()), "{:?}: {}", name, error);
+        assert!(serde_json::from_str::<RepoName>(&json).is_err(), "{:?}", name);
+    }
+}
+
+#[test]
+fn accepts_pisi_names() {
+    for name in ["nano", "gtk+3", "python3-lxml", "lib_foo.1", "7zip"] {
+        assert_eq!(PackageName::new(name).unwrap().as_str(), name);
+        assert_eq!(serde_json::from_str::<PackageName>(&format!("{:?}", name)).unwrap().as_str(), name);
+    }
+    assert_eq!(RepoName::new("pisi-2.0").unwrap().as_str(), "pisi-2.0");
+    // `+` sadece paket adlarında geçerli
+    assert!(matches!(RepoName::new("repo+1"), Err(NameError::InvalidCharacter { character: '+', .. })));
+}
+
+#[test]
+fn rejects_invalid_names_inside_command_payloads() {
+    let valid: TransactionAction = serde_json::from_str(r#"{"kind":"Install","package":"nano"}"#).unwrap();
+    assert_eq!(valid.package.as_str(), "nano");
+
+    for package in ["-rf", "--ignore-dependency", "", "a b", "a/b", "a\u{7}"] {
+        let json = serde_json::json!({ "kind": "Remove", "package": package }).to_string();
+        assert!(serde_json::from_str::<TransactionAction>(&json).is_err(), "{:?}", package);
+    }
+}
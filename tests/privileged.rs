@@ -0,0 +1,120 @@
+use pm_core::backend::operation::{run_pisi_with_progress, OperationPhase, OperationRegistry};
+use pm_core::backend::privileged::{self, HELPER_PATH_ENV};
+use pm_core::backend::runner::{PisiRunner, SystemPisiRunner};
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+
+mod common;
+use common::TempDir;
+
+const HELPER: &str = env!("CARGO_BIN_EXE_pisi-pm-helper");
+
+/// Sahte `pkexec` (argümanlarını kaydedip çalıştırır) ve sahte `pisi` (argümanlarını ve
+/// LC_ALL değerini kaydeder) içeren dizin
+fn fake_bin_dir() -> TempDir {
+    let dir = TempDir::new("privileged");
+    write_script(&dir.join("pkexec"), &format!(
+        "#!/bin/sh\necho \"$@\" >> {log}/pkexec.log\nexec \"$@\"\n",
+        log = dir.display()
+    ));
+    write_script(&dir.join("pisi"), &format!(
+        "#!/bin/sh\necho \"$LC_ALL $@\" >> {log}/pisi.log\necho \"Installing 1 / 1\"\n",
+        log = dir.display()
+    ));
+    dir
+}
+
+/// Sahte dizini öne alan `PATH`; test sürecinin ortamı değiştirilmez, sadece başlatılan komutlara verilir
+fn path_with(dir: &Path) -> String {
+    format!("{}:{}", dir.display(), std::env::var("PATH").unwrap_or_default())
+}
+
+fn runner(dir: &Path) -> SystemPisiRunner {
+    SystemPisiRunner::default()
+        .with_env("PATH", path_with(dir))
+        .with_env(HELPER_PATH_ENV, HELPER)
+}
+
+fn write_script(path: &Path, content: &str) {
+    fs::write(path, content).unwrap();
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755)).unwrap();
+}
+
+fn log_lines(dir: &Path, name: &str) -> Vec<String> {
+    fs::read_to_string(dir.join(name))
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+fn args(list: &[&str]) -> Vec<String> {
+    list.iter().map(|s| s.to_string()).collect()
+}
+
+#[test]
+fn accepts_whitelisted_verbs() {
+    assert!(privileged::validate_helper_args(&args(&["it", "-y", "--", "nano"])).is_ok());
+    assert!(privileged::validate_helper_args(&args(&["ur"])).is_ok());
+    assert!(privileged::validate_helper_args(&args(&["ar", "--at", "1", "--", "contrib", "https://example.org/pisi-index.xml.xz"])).is_ok());
+    assert!(privileged::validate_helper_args(&args(&["ar", "--no-fetch", "--at", "1", "--", "contrib", "https://example.org/pisi-index.xml.xz"])).is_ok());
+    assert!(privileged::validate_helper_args(&args(&["signal", "INT", "1234"])).is_ok());
+}
+
+#[test]
+fn rejects_other_verbs_and_options() {
+    for rejected in [
+        &[][..],
+        &["build", "nano.pspec"],
+        &["it", "--ignore-safety", "--", "nano"],
+        &["it", "-D/", "--", "nano"],
+        // Adlar `--` ayıracından sonra gelmeli, ayıraçtan sonra da seçenek kabul edilmez
+        &["it", "nano", "-y"],
+        &["it", "-y", "--", "--ignore-dependency"],
+        &["ar", "--at", "first", "--", "contrib", "url"],
+        &["signal", "TERM", "1234"],
+        &["signal", "INT", "self"],
+    ] {
+        assert!(privileged::validate_helper_args(&args(rejected)).is_err(), "{:?} was accepted", rejected);
+    }
+}
+
+#[tokio::test]
+async fn runs_pisi_through_pkexec_and_helper() {
+    let dir = fake_bin_dir();
+    let output = runner(&dir).output(&privileged::pisi_args("rm", &["-y"], &["nano"])).await.unwrap();
+    assert!(output.is_success(), "{}", output.stderr);
+
+    assert_eq!(log_lines(&dir, "pkexec.log"), [format!("{} rm -y -- nano", HELPER)]);
+    // Yardımcı, pkexec'in temizlediği dil ayarını kendisi verir
+    assert!(log_lines(&dir, "pisi.log").contains(&"C rm -y -- nano".to_string()));
+}
+
+#[test]
+fn helper_refuses_disallowed_commands() {
+    let dir = fake_bin_dir();
+    let output = privileged::helper_command(Path::new(HELPER), &["emerge", "nano"])
+        .env("PATH", path_with(&dir))
+        .output()
+        .unwrap();
+
+    assert_eq!(output.status.code(), Some(2));
+    assert!(String::from_utf8_lossy(&output.stderr).contains("Command not allowed: emerge"));
+    assert!(!log_lines(&dir, "pisi.log").iter().any(|l| l.contains("emerge")));
+}
+
+#[tokio::test]
+async fn progress_runner_uses_helper() {
+    let dir = fake_bin_dir();
+    let registry = OperationRegistry::default();
+    let mut phases = Vec::new();
+
+    run_pisi_with_progress(&runner(&dir), &registry, "install-test", &["it", "-y", "--", "curl"], |p| phases.push(p.phase))
+        .await
+        .unwrap();
+
+    assert_eq!(phases, [OperationPhase::Started, OperationPhase::Installing, OperationPhase::Finished]);
+    assert!(log_lines(&dir, "pisi.log").contains(&"C it -y -- curl".to_string()));
+    assert!(registry.list().is_empty());
+}
@@ -0,0 +1,157 @@
+use pm_core::backend::error::PmError;
+use pm_core::backend::names::RepoName;
+use pm_core::backend::repo_probe::{probe_index, RepositoryUrl, SystemDistribution, PROBE_HEAD_BYTES};
+use pm_core::backend::repository::RepositoryManager;
+use pm_core::backend::runner::{PisiOutput, ScriptedPisiRunner};
+use std::io::Write;
+use std::path::PathBuf;
+
+mod common;
+use common::{fixture_index, serve, TempDir};
+
+fn pisilinux_2_0() -> SystemDistribution {
+    SystemDistribution {
+        distribution: Some("PisiLinux".to_string()),
+        release: Some("2.0".to_string()),
+        architecture: Some("x86_64".to_string()),
+    }
+}
+
+#[test]
+fn validates_url_scheme_and_file_name() {
+    assert!(matches!(RepositoryUrl::parse("https://example.org/2.0/pisi-index.xml.xz"), Ok(RepositoryUrl::Http(_))));
+    assert_eq!(
+        RepositoryUrl::parse("file:///srv/repo/pisi-index.xml").unwrap(),
+        RepositoryUrl::Local(PathBuf::from("/srv/repo/pisi-index.xml"))
+    );
+    assert!(matches!(RepositoryUrl::parse("/srv/repo/pisi-index.xml.zst"), Ok(RepositoryUrl::Local(_))));
+
+    for invalid in [
+        "ftp://example.org/pisi-index.xml",
+        "example.org/pisi-index.xml",
+        "https:///pisi-index.xml",
+        "https://example.org/2.0/",
+        "https://example.org/2.0/index.html",
+        "https://example.org/my repo/pisi-index.xml",
+    ] {
+        assert!(matches!(RepositoryUrl::parse(invalid), Err(PmError::InvalidRequest { .. })), "{} was accepted", invalid);
+    }
+}
+
+#[tokio::test]
+async fn probes_plain_and_compressed_index_over_http() {
+    let index = std::fs::read(fixture_index()).unwrap();
+    let mut compressed = Vec::new();
+    let mut encoder = xz2::write::XzEncoder::new(&mut compressed, 6);
+    encoder.write_all(&index).unwrap();
+    // Baş kısmın ötesinde kalan ve hiç indirilmemesi gereken dolgu
+    encoder.write_all(&vec![b' '; PROBE_HEAD_BYTES * 2]).unwrap();
+    encoder.finish().unwrap();
+
+    let base = serve(vec![("/2.0/pisi-index.xml", index), ("/2.0/pisi-index.xml.xz", compressed)]).await;
+
+    for file in ["pisi-index.xml", "pisi-index.xml.xz"] {
+        let probe = probe_index(&format!("{}/2.0/{}", base, file), &pisilinux_2_0()).await.unwrap();
+        assert_eq!(probe.distribution.as_deref(), Some("PisiLinux"));
+        assert_eq!(probe.release.as_deref(), Some("2.0"));
+        assert_eq!(probe.architecture.as_deref(), Some("x86_64"));
+        assert!(probe.mismatches.is_empty(), "{:?}", probe.mismatches);
+    }
+}
+
+#[tokio::test]
+async fn reports_distribution_and_architecture_mismatches() {
+    let base = serve(vec![("/pisi-index.xml", std::fs::read(fixture_index()).unwrap())]).await;
+    let system = SystemDistribution {
+        distribution: Some("PisiLinux".to_string()),
+        release: Some("2.1".to_string()),
+        architecture: Some("i686".to_string()),
+    };
+
+    let probe = probe_index(&format!("{}/pisi-index.xml", base), &system).await.unwrap();
+    let fields: Vec<_> = probe.mismatches.iter().map(|m| (m.field.as_str(), m.expected.as_str(), m.found.as_str())).collect();
+    assert_eq!(fields, [("release", "2.1", "2.0"), ("architecture", "i686", "x86_64")]);
+}
+
+#[tokio::test]
+async fn rejects_missing_and_non_pisi_indexes() {
+    let html = b"<html><body>Not a repository</body></html>".to_vec();
+    let no_distribution = b"<PISI><Package><Name>nano</Name></Package></PISI>".to_vec();
+    let base = serve(vec![("/html/pisi-index.xml", html), ("/bare/pisi-index.xml", no_distribution)]).await;
+
+    for (path, expected) in [
+        ("/missing/pisi-index.xml", "HTTP 404"),
+        ("/html/pisi-index.xml", "expected <PISI>"),
+        ("/bare/pisi-index.xml", "before the <Distribution> block"),
+    ] {
+        match probe_index(&format!("{}{}", base, path), &pisilinux_2_0()).await {
+            Err(PmError::InvalidRequest { message }) => assert!(message.contains(expected), "{}: {}", path, message),
+            other => panic!("{}: unexpected result {:?}", path, other),
+        }
+    }
+}
+
+#[tokio::test]
+async fn unreachable_server_is_a_network_error() {
+    // Dinlemeyen bir port: bağlantı reddedilir
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+
+    let error = probe_index(&format!("http://{}/pisi-index.xml", address), &pisilinux_2_0()).await.unwrap_err();
+    assert!(matches!(error, PmError::NetworkUnavailable { .. }));
+    assert!(error.is_retryable());
+}
+
+#[tokio::test]
+async fn probes_local_file_and_reads_pisi_conf() {
+    let dir = TempDir::new("probe");
+    std::fs::copy(fixture_index(), dir.join("pisi-index.xml")).unwrap();
+    std::fs::write(
+        dir.join("pisi.conf"),
+        "[general]\n# architecture = i686\narchitecture = aarch64\ndistribution = PisiLinux\n\n[build]\narchitecture = x86_64\n",
+    ).unwrap();
+
+    let system = SystemDistribution::from_pisi_conf(&dir.join("pisi.conf"));
+    assert_eq!(system.architecture.as_deref(), Some("aarch64"));
+    assert_eq!(system.release, None);
+
+    let probe = probe_index(&format!("file://{}/pisi-index.xml", dir.display()), &system).await.unwrap();
+    assert_eq!(probe.mismatches.len(), 1);
+    assert_eq!(probe.mismatches[0].field, "architecture");
+
+    let error = probe_index(&format!("{}/missing/pisi-index.xml", dir.display()), &system).await.unwrap_err();
+    assert!(matches!(error, PmError::InvalidRequest { .. }));
+}
+
+#[tokio::test]
+async fn add_repository_probes_the_index_before_calling_pisi() {
+    let dir = TempDir::new("probe-add");
+    std::fs::copy(fixture_index(), dir.join("pisi-index.xml")).unwrap();
+    std::fs::create_dir(dir.join("html")).unwrap();
+    std::fs::write(dir.join("html/pisi-index.xml"), "<html><body>Not Found</body></html>").unwrap();
+    std::fs::create_dir(dir.join("empty")).unwrap();
+    std::fs::write(dir.join("empty/pisi-index.xml"), "<PISI></PISI>").unwrap();
+
+    let url = format!("{}/pisi-index.xml", dir.display());
+    let runner = ScriptedPisiRunner::new()
+        .expect(&["add-repo", "--at", "1", "--", "local", &url], PisiOutput::success(""));
+    let manager = RepositoryManager::new(&runner).with_index_dir("/nonexistent");
+    let name = RepoName::new("local").unwrap();
+
+    // PiSi indeksi olmayan, <Distribution> bloğu eksik, bulunmayan ve başka mimari için hazırlanmış indeksler
+    let aarch64 = SystemDistribution { architecture: Some("aarch64".to_string()), ..pisilinux_2_0() };
+    for (url, system) in [
+        (format!("{}/html/pisi-index.xml", dir.display()), pisilinux_2_0()),
+        (format!("{}/empty/pisi-index.xml", dir.display()), pisilinux_2_0()),
+        (format!("{}/missing/pisi-index.xml", dir.display()), pisilinux_2_0()),
+        (url.clone(), aarch64),
+    ] {
+        let error = manager.add_repository(&name, &url, &system).await.unwrap_err();
+        assert!(matches!(error, PmError::InvalidRequest { .. }), "{}: {:?}", url, error);
+    }
+    assert!(runner.calls().is_empty());
+
+    manager.add_repository_at(&name, &url, 1, &pisilinux_2_0()).await.unwrap();
+    assert_eq!(runner.calls().len(), 1);
+}
@@ -0,0 +1,90 @@
+use pm_core::backend::index_verify::IndexVerifier;
+use pm_core::backend::xml_parser::XmlParser;
+use std::fs;
+use std::path::Path;
+
+mod common;
+use common::TempDir;
+
+/// `name` deposuna, verilen sürümde tek bir `nano` paketi olan indeks yazar
+fn write_repo(index_dir: &Path, name: &str, version: &str) {
+    let dir = index_dir.join(name);
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("pisi-index.xml"), format!(
+        "<PISI><Package><Name>nano</Name><History><Update release=\"1\"><Version>{}</Version></Update></History></Package></PISI>",
+        version
+    )).unwrap();
+}
+
+fn write_order(path: &Path, repos: &[(&str, &str)]) {
+    let repos: String = repos
+        .iter()
+        .map(|(name, status)| format!("<Repo><Name>{}</Name><Status>{}</Status></Repo>", name, status))
+        .collect();
+    fs::write(path, format!("<REPOS>{}</REPOS>", repos)).unwrap();
+}
+
+fn temp_root(test: &str) -> TempDir {
+    let root = TempDir::new(&format!("order-{}", test));
+    fs::create_dir_all(root.join("index")).unwrap();
+    root
+}
+
+#[test]
+fn first_repository_in_order_wins() {
+    let root = temp_root("wins");
+    let (index_dir, order) = (root.join("index"), root.join("repos"));
+    write_repo(&index_dir, "stable", "7.2");
+    write_repo(&index_dir, "contrib", "8.0");
+
+    write_order(&order, &[("stable", "active"), ("contrib", "active")]);
+    let repos = XmlParser::discover_repositories_in(&index_dir, &order).unwrap();
+    let packages = XmlParser::load_repositories(&repos).unwrap();
+    assert_eq!((packages[0].repository.as_str(), packages[0].version.as_str()), ("stable", "7.2"));
+
+    // contrib yukarı taşındıktan sonra nano contrib'den gelir
+    write_order(&order, &[("contrib", "active"), ("stable", "active")]);
+    let repos = XmlParser::discover_repositories_in(&index_dir, &order).unwrap();
+    let names: Vec<_> = repos.iter().map(|r| (r.name.as_str(), r.priority)).collect();
+    assert_eq!(names, [("contrib", 0), ("stable", 1)]);
+    let packages = XmlParser::load_repositories(&repos).unwrap();
+    assert_eq!((packages[0].repository.as_str(), packages[0].version.as_str()), ("contrib", "8.0"));
+}
+
+#[test]
+fn inactive_repositories_are_skipped() {
+    let root = temp_root("inactive");
+    let (index_dir, order) = (root.join("index"), root.join("repos"));
+    write_repo(&index_dir, "stable", "7.2");
+    write_repo(&index_dir, "contrib", "8.0");
+    write_order(&order, &[("contrib", "inactive"), ("stable", "active")]);
+
+    let repos = XmlParser::discover_repositories_in(&index_dir, &order).unwrap();
+    let packages = XmlParser::load_repositories(&repos).unwrap();
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].repository, "stable");
+}
+
+#[test]
+fn unreadable_repository_is_skipped() {
+    let root = temp_root("corrupt");
+    let (index_dir, order) = (root.join("index"), root.join("repos"));
+    write_repo(&index_dir, "stable", "7.2");
+    fs::create_dir_all(index_dir.join("contrib")).unwrap();
+    // Yarıda kesilmiş indirme
+    fs::write(index_dir.join("contrib/pisi-index.xml"), "<PISI><Package><Name>nano</Na").unwrap();
+    write_order(&order, &[("contrib", "active"), ("stable", "active")]);
+
+    let repos = XmlParser::discover_repositories_in(&index_dir, &order).unwrap();
+    let packages = XmlParser::load_repositories(&repos).unwrap();
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].repository, "stable");
+    let packages = XmlParser::load_verified_repositories(&repos, &IndexVerifier::default(), None).unwrap();
+    assert_eq!(packages.len(), 1);
+    assert_eq!(packages[0].repository, "stable");
+
+    // Okunabilen depo kalmazsa parse hatası döner
+    let contrib: Vec<_> = repos.into_iter().filter(|r| r.name == "contrib").collect();
+    assert!(XmlParser::load_repositories(&contrib).is_err());
+    assert!(XmlParser::load_verified_repositories(&contrib, &IndexVerifier::default(), None).is_err());
+}
@@ -0,0 +1,257 @@
+use pm_core::backend::error::PmError;
+use pm_core::backend::installed_db::{InstallReason, InstalledPackage};
+use pm_core::backend::names::PackageName;
+use pm_core::backend::resolver::{PlanReason, Resolver, TransactionPlan};
+use pm_core::backend::xml_parser::{PackageInfo, XmlParser};
+
+/// İndeks kaydı: `(ad, sürüm, yayın, ek XML)`
+type Pkg<'a> = (&'a str, &'a str, u32, &'a str);
+/// `(açıklama, indeks, kurulu, istenen, kurulacak, yükseltilecek, kaldırılacak)`
+type PlanCase<'a> = (&'a str, Vec<Pkg<'a>>, Vec<Pkg<'a>>, &'a [&'a str], &'a [&'a str], &'a [&'a str], &'a [&'a str]);
+/// `(açıklama, indeks, kurulu, istenen, beklenen hata)`
+type ErrorCase<'a> = (&'a str, Vec<Pkg<'a>>, Vec<Pkg<'a>>, &'a [&'a str], &'a str);
+
+fn index(packages: &[Pkg]) -> Vec<PackageInfo> {
+    let body: String = packages
+        .iter()
+        .map(|(name, version, release, extra)| {
+            format!(
+                "<Package><Name>{}</Name>{}<History><Update release=\"{}\"><Date>2024-01-01</Date><Version>{}</Version></Update></History></Package>",
+                name, extra, release, version
+            )
+        })
+        .collect();
+    XmlParser::parse_pisi_index(&format!("<PISI>{}</PISI>", body)).unwrap()
+}
+
+/// Kurulu paketler aynı ek XML'den (bağımlılık ve çakışmalar) okunur
+fn installed(packages: &[Pkg]) -> Vec<InstalledPackage> {
+    index(packages)
+        .into_iter()
+        .map(|p| InstalledPackage {
+            name: p.name,
+            version: p.version,
+            release: p.release,
+            summary: String::new(),
+            part_of: String::new(),
+            distribution: String::new(),
+            distribution_release: String::new(),
+            architecture: String::new(),
+            installed_size: 100,
+            install_date: None,
+            reason: InstallReason::Explicit,
+            dependencies: p.dependencies,
+            conflicts: p.conflicts,
+        })
+        .collect()
+}
+
+fn deps(names: &str) -> String {
+    format!("<RuntimeDependencies>{}</RuntimeDependencies>", names)
+}
+
+fn plan(index_packages: &[Pkg], installed_packages: &[Pkg], requested: &[&str]) -> Result<TransactionPlan, PmError> {
+    let index = index(index_packages);
+    let installed = installed(installed_packages);
+    let names: Vec<PackageName> = requested.iter().map(|n| PackageName::new(*n).unwrap()).collect();
+    Resolver::new(&index, &installed).plan_install(&names)
+}
+
+fn names(packages: &[pm_core::backend::resolver::PlannedPackage]) -> Vec<&str> {
+    packages.iter().map(|p| p.name.as_str()).collect()
+}
+
+#[test]
+fn plan_table() {
+    let lib_dep = deps("<Dependency>lib</Dependency>");
+    let app_deps = deps("<Dependency>lib</Dependency><Dependency>util</Dependency>");
+    let util_dep = deps("<Dependency>base</Dependency>");
+    let cycle_a = deps("<Dependency>b</Dependency>");
+    let cycle_b = deps("<Dependency>a</Dependency>");
+    let lib_from_2 = deps("<Dependency versionFrom=\"2.0\">lib</Dependency>");
+    let lib_exact_1 = deps("<Dependency version=\"1.0\">lib</Dependency>");
+    let lib_from_1 = deps("<Dependency versionFrom=\"1.0\">lib</Dependency>");
+
+    let cases: &[PlanCase] = &[
+        (
+            "dependencies come before dependents",
+            vec![("app", "1.0", 1, &app_deps), ("lib", "1.0", 1, ""), ("util", "1.0", 1, &util_dep), ("base", "1.0", 1, "")],
+            vec![],
+            &["app"],
+            &["lib", "base", "util", "app"],
+            &[],
+            &[],
+        ),
+        (
+            "installed dependencies are not reinstalled",
+            vec![("app", "1.0", 1, &app_deps), ("lib", "1.0", 1, ""), ("util", "1.0", 1, &util_dep), ("base", "1.0", 1, "")],
+            vec![("lib", "1.0", 1, ""), ("base", "1.0", 1, "")],
+            &["app"],
+            &["util", "app"],
+            &[],
+            &[],
+        ),
+        (
+            "cycles are visited once",
+            vec![("a", "1.0", 1, &cycle_a), ("b", "1.0", 1, &cycle_b)],
+            vec![],
+            &["a"],
+            &["b", "a"],
+            &[],
+            &[],
+        ),
+        (
+            "already up-to-date request is a no-op",
+            vec![("lib", "1.0", 1, "")],
+            vec![("lib", "1.0", 1, "")],
+            &["lib"],
+            &[],
+            &[],
+            &[],
+        ),
+        (
+            "newer release is an upgrade",
+            vec![("lib", "1.0", 2, "")],
+            vec![("lib", "1.0", 1, "")],
+            &["lib"],
+            &[],
+            &["lib"],
+            &[],
+        ),
+        (
+            "outdated dependency is upgraded",
+            vec![("app", "1.0", 1, &lib_from_2), ("lib", "2.1", 1, "")],
+            vec![("lib", "1.5", 1, "")],
+            &["app"],
+            &["app"],
+            &["lib"],
+            &[],
+        ),
+        (
+            "conflicting installed package is removed",
+            vec![("nano", "7.2", 1, "<Conflicts><Package>nano-tiny</Package></Conflicts>")],
+            vec![("nano-tiny", "1.0", 1, "")],
+            &["nano"],
+            &["nano"],
+            &[],
+            &["nano-tiny"],
+        ),
+        (
+            "conflict with a version outside the range is kept",
+            vec![("nano", "7.2", 1, "<Conflicts><Package versionTo=\"0.9\">nano-tiny</Package></Conflicts>")],
+            vec![("nano-tiny", "1.0", 1, "")],
+            &["nano"],
+            &["nano"],
+            &[],
+            &[],
+        ),
+        (
+            "installed package declaring a conflict is removed",
+            vec![("nano", "7.2", 1, "")],
+            vec![("nano-tiny", "1.0", 1, "<Conflicts><Package>nano</Package></Conflicts>")],
+            &["nano"],
+            &["nano"],
+            &[],
+            &["nano-tiny"],
+        ),
+        (
+            "replaced package is removed",
+            vec![("python3-foo", "2.0", 1, "<Replaces><Package>foo</Package></Replaces>")],
+            vec![("foo", "1.0", 1, "")],
+            &["python3-foo"],
+            &["python3-foo"],
+            &[],
+            &["foo"],
+        ),
+        (
+            "dependent satisfied by the upgraded version",
+            vec![("lib", "2.0", 1, "")],
+            vec![("lib", "1.0", 1, ""), ("app", "1.0", 1, &lib_from_1)],
+            &["lib"],
+            &[],
+            &["lib"],
+            &[],
+        ),
+    ];
+
+    for (description, index, installed, requested, install, upgrade, remove) in cases {
+        let plan = plan(index, installed, requested).unwrap_or_else(|e| panic!("{}: {}", description, e));
+        assert_eq!(names(&plan.install), *install, "{}: install", description);
+        assert_eq!(names(&plan.upgrade), *upgrade, "{}: upgrade", description);
+        assert_eq!(names(&plan.remove), *remove, "{}: remove", description);
+    }
+
+    // Aynı tablonun hata veren satırları
+    let errors: &[ErrorCase] = &[
+        ("unknown package", vec![], vec![], &["ghost"], "Package ghost not found"),
+        ("missing dependency", vec![("app", "1.0", 1, &lib_dep)], vec![], &["app"], "app requires lib, which is not available"),
+        (
+            "unsatisfiable version constraint",
+            vec![("app", "1.0", 1, &lib_from_2), ("lib", "1.9", 1, "")],
+            vec![],
+            &["app"],
+            "app requires lib >= 2.0, but only 1.9-1 is available",
+        ),
+        (
+            "both sides of a conflict requested",
+            vec![
+                ("nano", "7.2", 1, "<Conflicts><Package>nano-tiny</Package></Conflicts>"),
+                ("nano-tiny", "1.0", 1, ""),
+            ],
+            vec![],
+            &["nano", "nano-tiny"],
+            "nano conflicts with nano-tiny",
+        ),
+        (
+            "upgrade breaks an installed dependent",
+            vec![("lib", "2.0", 1, "")],
+            vec![("lib", "1.0", 1, ""), ("app", "1.0", 1, &lib_exact_1)],
+            &["lib"],
+            "Upgrading lib from 1.0-1 to 2.0-1 breaks installed package app, which requires lib = 1.0",
+        ),
+        (
+            "dependency upgrade breaks an installed dependent",
+            vec![("tool", "1.0", 1, &lib_from_2), ("lib", "2.0", 1, "")],
+            vec![("lib", "1.0", 1, ""), ("app", "1.0", 1, &lib_exact_1)],
+            &["tool"],
+            "breaks installed package app",
+        ),
+    ];
+
+    for (description, index, installed, requested, expected) in errors {
+        match plan(index, installed, requested) {
+            Err(e) => {
+                assert!(e.to_string().contains(expected), "{}: {}", description, e);
+                // Sadece istenen paketin bulunamaması çakışma sayılmaz
+                let kind_matches = match e {
+                    PmError::PackageNotFound { .. } => *description == "unknown package",
+                    PmError::DependencyConflict { .. } => *description != "unknown package",
+                    _ => false,
+                };
+                assert!(kind_matches, "{}: {:?}", description, e);
+            }
+            Ok(plan) => panic!("{}: unexpected plan {:?}", description, plan),
+        }
+    }
+}
+
+#[test]
+fn plan_reasons_and_sizes() {
+    let app_deps = deps("<Dependency versionFrom=\"2.0\">lib</Dependency>");
+    let plan = plan(
+        &[
+            ("app", "1.0", 1, &format!("{}<Conflicts><Package>old-app</Package></Conflicts>", app_deps)),
+            ("lib", "2.0", 1, ""),
+        ],
+        &[("lib", "1.0", 1, ""), ("old-app", "0.1", 1, "")],
+        &["app"],
+    )
+    .unwrap();
+
+    assert_eq!(plan.install[0].reason, PlanReason::Requested);
+    assert_eq!(plan.upgrade[0].reason, PlanReason::Dependency { required_by: "app".to_string() });
+    assert_eq!(plan.upgrade[0].installed_version.as_deref(), Some("1.0-1"));
+    assert_eq!(plan.remove[0].reason, PlanReason::Conflict { with: "app".to_string() });
+    // Eski lib ve kaldırılan old-app (her biri 100 bayt)
+    assert_eq!(plan.freed_size, 200);
+}
@@ -0,0 +1,28 @@
+use pm_core::config::settings::AppSettings;
+use std::path::PathBuf;
+
+// Ortam değişkenlerini değiştirdiği için ayrı test ikilisinde ve tek test olarak durur
+#[test]
+fn user_dirs_never_fall_back_to_a_shared_directory() {
+    std::env::set_var("XDG_CACHE_HOME", "/home/user/.xdg-cache");
+    std::env::set_var("XDG_CONFIG_HOME", "/home/user/.xdg-config");
+    assert_eq!(AppSettings::cache_dir(), Some(PathBuf::from("/home/user/.xdg-cache/pisi-package-manager")));
+    assert_eq!(
+        AppSettings::config_path(),
+        Some(PathBuf::from("/home/user/.xdg-config/pisi-package-manager/settings.json"))
+    );
+
+    // Göreli XDG yolları yok sayılır
+    std::env::set_var("XDG_CACHE_HOME", "relative");
+    std::env::remove_var("XDG_CONFIG_HOME");
+    std::env::set_var("HOME", "/home/user");
+    assert_eq!(AppSettings::cache_dir(), Some(PathBuf::from("/home/user/.cache/pisi-package-manager")));
+    assert_eq!(AppSettings::config_path(), Some(PathBuf::from("/home/user/.config/pisi-package-manager/settings.json")));
+
+    std::env::remove_var("XDG_CACHE_HOME");
+    std::env::remove_var("HOME");
+    assert_eq!(AppSettings::cache_dir(), None);
+    assert_eq!(AppSettings::config_path(), None);
+    assert!(AppSettings::default().save().is_err());
+    assert_eq!(AppSettings::load().mirror_list, AppSettings::default().mirror_list);
+}
@@ -0,0 +1,135 @@
+use pm_core::backend::dependency::DependencyConstraint;
+use pm_core::backend::version::{PackageVersion, Version};
+use std::cmp::Ordering;
+
+fn v(s: &str) -> Version {
+    Version::parse(s).unwrap_or_else(|e| panic!("{}: {}", s, e))
+}
+
+#[test]
+fn version_ordering_table() {
+    use Ordering::*;
+
+    let cases = [
+        // Sayısal parçalar
+        ("1.0", "1.0", Equal),
+        ("1.0", "1.1", Less),
+        ("1.10", "1.9", Greater),
+        ("2.30", "2.4", Greater),
+        ("1.0", "1.0.0", Less),
+        ("1.0.1", "1.0", Greater),
+        ("10", "9.9.9", Greater),
+        ("1.01", "1.1", Equal),
+        // Parça sonundaki harfler
+        ("1.0a", "1.0", Greater),
+        ("1.0a", "1.0b", Less),
+        ("1.0z", "1.1", Less),
+        // Ekler
+        ("1.0_alpha", "1.0_beta", Less),
+        ("1.0_beta", "1.0_pre", Less),
+        ("1.0_pre", "1.0_rc", Less),
+        ("1.0_rc", "1.0", Less),
+        ("1.0", "1.0_p", Less),
+        ("1.0_alpha", "1.0", Less),
+        ("1.0_p1", "1.0_p2", Less),
+        ("1.0_rc1", "1.0_rc2", Less),
+        ("1.0_rc10", "1.0_rc9", Greater),
+        ("1.0_beta2", "1.0_rc1", Less),
+        ("1.0_p1", "1.1_alpha", Less),
+        ("1.0_rc", "1.0_rc0", Equal),
+        // Birden fazla ek
+        ("1.0_rc1", "1.0_rc1_p1", Less),
+        ("1.0_rc1_p1", "1.0_rc2", Less),
+        ("1.0_beta1_alpha1", "1.0_beta1", Less),
+        // Sürümün ek ile değil parçayla kazanması
+        ("1.1_alpha", "1.0_p9", Greater),
+        ("5.15.2_rc3", "5.15.1_p4", Greater),
+    ];
+
+    for (a, b, expected) in cases {
+        assert_eq!(v(a).cmp(&v(b)), expected, "{} vs {}", a, b);
+        assert_eq!(v(b).cmp(&v(a)), expected.reverse(), "{} vs {} (reversed)", b, a);
+    }
+}
+
+#[test]
+fn invalid_versions_are_rejected() {
+    for s in ["", "   ", "abc", "1..0", "1.0_gamma", "1.0_rc_x", "1.0-2", ".1", "1.0a1"] {
+        assert!(Version::parse(s).is_err(), "{:?} should be invalid", s);
+    }
+}
+
+#[test]
+fn package_version_compares_release_after_version() {
+    let cases = [
+        (("1.0", 3), ("1.0", 4), Ordering::Less),
+        (("1.0", 9), ("1.0.1", 1), Ordering::Less),
+        (("1.0_p1", 1), ("1.0", 7), Ordering::Greater),
+        (("2.4", 2), ("2.4", 2), Ordering::Equal),
+    ];
+
+    for ((av, ar), (bv, br), expected) in cases {
+        let a = PackageVersion::new(av, ar).unwrap();
+        let b = PackageVersion::new(bv, br).unwrap();
+        assert_eq!(a.cmp(&b), expected, "{} vs {}", a, b);
+    }
+}
+
+#[test]
+fn version_round_trips_through_serde() {
+    let version = v("3.2_rc1");
+    let json = serde_json::to_string(&version).unwrap();
+    assert_eq!(json, "\"3.2_rc1\"");
+    assert_eq!(serde_json::from_str::<Version>(&json).unwrap(), version);
+    assert!(serde_json::from_str::<Version>("\"not a version\"").is_err());
+}
+
+#[test]
+fn dependency_constraint_table() {
+    let glibc_range = DependencyConstraint {
+        version_from: Some("2.30".into()),
+        version_to: Some("2.40".into()),
+        ..Default::default()
+    };
+    let exact = DependencyConstraint {
+        version: Some("1.2".into()),
+        release: Some(3),
+        ..Default::default()
+    };
+    let release_range = DependencyConstraint {
+        release_from: Some(5),
+        release_to: Some(7),
+        ..Default::default()
+    };
+
+    let cases = [
+        (&glibc_range, "2.30", 1, true),
+        (&glibc_range, "2.35", 1, true),
+        (&glibc_range, "2.40", 1, true),
+        (&glibc_range, "2.29", 9, false),
+        (&glibc_range, "2.41", 1, false),
+        (&glibc_range, "2.30_rc1", 1, false),
+        (&exact, "1.2", 3, true),
+        (&exact, "1.2", 4, false),
+        (&exact, "1.2.0", 3, false),
+        (&release_range, "0.1", 5, true),
+        (&release_range, "99", 7, true),
+        (&release_range, "1.0", 4, false),
+        (&release_range, "1.0", 8, false),
+    ];
+
+    for (constraint, version, release, expected) in cases {
+        assert_eq!(
+            constraint.is_satisfied_by(version, release),
+            expected,
+            "{} {}-{} against {}",
+            if expected { "accept" } else { "reject" },
+            version,
+            release,
+            constraint
+        );
+    }
+
+    assert!(DependencyConstraint::default().is_satisfied_by("anything", 0));
+    assert_eq!(glibc_range.to_string(), ">= 2.30, <= 2.40");
+}